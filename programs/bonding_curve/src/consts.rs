@@ -0,0 +1,51 @@
+/// Denominator for every basis-point value in the program.
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Fixed-point scale of `CurveConfiguration::proportion` (6 decimals, so 9.14 is 9_140_000).
+pub const PROPORTION_PRECISION: u128 = 1_000_000;
+
+/// Token base units in one curve unit (one million whole tokens with 9 decimals).
+pub const CURVE_TOKEN_UNIT: u128 = 1_000_000_000_000_000;
+
+/// Exchange token base units in one whole exchange token.
+pub const CURVE_EXCHANGE_UNIT: u128 = 1_000_000_000;
+
+/// Share of the total supply, in basis points, sold through the curve before it is exhausted.
+pub const GRADUATION_BPS: u64 = 8_000;
+
+/// Token base units in one whole token, the unit curve prices are quoted per.
+pub const PRICE_TOKEN_UNIT: u128 = 1_000_000_000;
+
+/// One in the 12-decimal fixed-point format used for curve multipliers.
+pub const FIXED_POINT_ONE: u128 = 1_000_000_000_000;
+
+/// Largest `CurveKind::Exponential::growth_bps` (10% per curve unit sold).
+pub const MAX_GROWTH_BPS: u64 = 1_000;
+
+/// Largest `CurveKind::Linear::slope` (1000 exchange tokens per whole token per curve unit).
+pub const MAX_CURVE_SLOPE: u64 = 1_000_000_000_000;
+
+/// `CurveConfiguration::pause_flags` bit stopping create_token.
+pub const PAUSE_CREATION: u8 = 1 << 0;
+
+/// `CurveConfiguration::pause_flags` bit stopping buy and buy_exact_out.
+pub const PAUSE_BUYS: u8 = 1 << 1;
+
+/// `CurveConfiguration::pause_flags` bit stopping sell and sell_exact_out.
+pub const PAUSE_SELLS: u8 = 1 << 2;
+
+/// `CurveConfiguration::pause_flags` bit stopping create_pool and add_liquidity.
+pub const PAUSE_POOL_CREATION: u8 = 1 << 3;
+
+/// `CurveConfiguration::pause_flags` bit stopping migrate and proxy_initialize.
+pub const PAUSE_MIGRATION: u8 = 1 << 4;
+
+/// Every pause bit, what the old `is_lockdown = true` maps to.
+pub const PAUSE_ALL: u8 =
+    PAUSE_CREATION | PAUSE_BUYS | PAUSE_SELLS | PAUSE_POOL_CREATION | PAUSE_MIGRATION;
+
+/// Entries in `CurveConfiguration::fee_recipients`, the fee distribution table.
+pub const MAX_FEE_RECIPIENTS: usize = 5;
+
+/// Seconds a queued configuration change waits before it can be executed (48 hours).
+pub const CONFIG_CHANGE_DELAY: i64 = 48 * 60 * 60;
@@ -85,4 +85,91 @@ pub enum CustomError {
 
     #[msg("Overflow")]
     Overflow,
+
+    #[msg("Invalid curve parameters")]
+    InvalidCurveParameters,
+
+    #[msg("Max input amount exceeded")]
+    MaxInputAmountExceeded,
+
+    #[msg("Amount exceeds what is left on the curve")]
+    AmountExceedsCurve,
+
+    #[msg("Bonding curve is complete")]
+    CurveCompleted,
+
+    #[msg("Bonding curve is not complete")]
+    CurveNotComplete,
+
+    #[msg("Pool has already been migrated")]
+    AlreadyMigrated,
+
+    #[msg("Action not allowed in the pool's current status")]
+    InvalidPoolStatus,
+
+    #[msg("No admin transfer is pending")]
+    NoPendingAdmin,
+
+    #[msg("A configuration change is already queued")]
+    ConfigChangePending,
+
+    #[msg("No configuration change is queued")]
+    NoPendingConfigChange,
+
+    #[msg("Configuration change is still timelocked")]
+    ConfigChangeNotReady,
+
+    #[msg("Account layout is already up to date")]
+    AccountUpToDate,
+
+    #[msg("Trading on this pool is halted")]
+    PoolHalted,
+
+    #[msg("Invalid referrer")]
+    InvalidReferrer,
+
+    #[msg("Fee recipients must be distinct and their weights must add up to 10000 bps")]
+    InvalidFeeRecipients,
+
+    #[msg("No fee recipients are configured")]
+    NoFeeRecipients,
+
+    #[msg("Buy is larger than the pool's early-trading limit")]
+    BuyLimitExceeded,
+
+    #[msg("Wallet would hold more than the pool's early-trading limit")]
+    WalletLimitExceeded,
+
+    #[msg("Pool is in its presale, buy through presale_buy")]
+    PresaleActive,
+
+    #[msg("Pool has no presale running")]
+    PresaleNotActive,
+
+    #[msg("Merkle proof does not match the presale allowlist")]
+    InvalidMerkleProof,
+
+    #[msg("Buy exceeds the wallet's presale allocation")]
+    PresaleAllocationExceeded,
+
+    #[msg("Pool is not open for trading yet")]
+    PoolNotOpen,
+
+    #[msg("Transaction deadline has passed")]
+    DeadlineExceeded,
+
+    #[msg("Trade moves the price more than allowed")]
+    PriceImpactTooHigh,
+
+    #[msg("Position is still used by the trade limits or the presale")]
+    PositionInUse,
+
+    #[msg("Buy needs the wallet's user position while trade limits or a presale apply")]
+    UserPositionRequired,
+
+    #[msg("Presale must end after now and after the pool opens")]
+    InvalidPresaleEnd,
+
+    #[msg("Fee vault for the configured creation fee mode is missing")]
+    FeeVaultRequired,
 }
@@ -4,15 +4,27 @@ use anchor_spl::{
     token::{Mint, Token, TokenAccount},
 };
 
-use crate::{errors::CustomError, state::{CurveConfiguration, LiquidityPool, LiquidityPoolAccount}};
+use crate::{consts::PAUSE_POOL_CREATION, errors::CustomError, state::{CurveConfiguration, LiquidityPool, LiquidityPoolAccount}};
 
-pub fn add_liquidity(ctx: Context<AddLiquidity>) -> Result<()> {
+// `open_time` lets the creator reschedule the open set at create_pool
+pub fn add_liquidity(ctx: Context<AddLiquidity>, open_time: Option<i64>) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
 
-    if ctx.accounts.dex_configuration_account.get_is_lockdown() == true {
+    if ctx.accounts.dex_configuration_account.is_paused(PAUSE_POOL_CREATION) {
         return err!(CustomError::Lockdown);
     }
 
+    if pool.halted {
+        return err!(CustomError::PoolHalted);
+    }
+
+    if let Some(open_time) = open_time {
+        if pool.creator != ctx.accounts.user.key() {
+            return err!(CustomError::NotCreator);
+        }
+        pool.set_open_time(open_time)?;
+    }
+
     let token_accounts = (
         &mut *ctx.accounts.token_mint,
         &mut *ctx.accounts.pool_token_account,
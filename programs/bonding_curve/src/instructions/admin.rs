@@ -0,0 +1,133 @@
+use crate::{errors::CustomError, state::*};
+use anchor_lang::prelude::*;
+
+pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    let dex_config = &mut ctx.accounts.dex_configuration_account;
+
+    dex_config.propose_admin(new_admin)?;
+
+    emit!(AdminProposedEvent {
+        admin: dex_config.get_admin(),
+        pending_admin: new_admin,
+    });
+
+    Ok(())
+}
+
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let dex_config = &mut ctx.accounts.dex_configuration_account;
+    let previous_admin = dex_config.get_admin();
+
+    dex_config.accept_admin(ctx.accounts.pending_admin.key())?;
+
+    emit!(AdminTransferredEvent {
+        previous_admin,
+        admin: dex_config.get_admin(),
+    });
+
+    Ok(())
+}
+
+pub fn cancel_admin_proposal(ctx: Context<CancelAdminProposal>) -> Result<()> {
+    let dex_config = &mut ctx.accounts.dex_configuration_account;
+    let pending_admin = dex_config.get_pending_admin();
+
+    dex_config.cancel_admin_proposal()?;
+
+    emit!(AdminProposalCancelledEvent {
+        admin: dex_config.get_admin(),
+        pending_admin,
+    });
+
+    Ok(())
+}
+
+pub fn set_roles(ctx: Context<SetRoles>, update: RoleUpdate) -> Result<()> {
+    let dex_config = &mut ctx.accounts.dex_configuration_account;
+
+    dex_config.set_roles(update)?;
+
+    emit!(RolesUpdatedEvent {
+        fee_manager: dex_config.get_fee_manager(),
+        pause_guardian: dex_config.get_pause_guardian(),
+        migration_operator: dex_config.get_migration_operator(),
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct AdminProposedEvent {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferredEvent {
+    pub previous_admin: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct AdminProposalCancelledEvent {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct RolesUpdatedEvent {
+    pub fee_manager: Pubkey,
+    pub pause_guardian: Pubkey,
+    pub migration_operator: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+        constraint = dex_configuration_account.get_admin() == admin.key() @ CustomError::InvalidAuthority,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    pub pending_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAdminProposal<'info> {
+    #[account(
+        mut,
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+        constraint = dex_configuration_account.get_admin() == admin.key() @ CustomError::InvalidAuthority,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRoles<'info> {
+    #[account(
+        mut,
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+        constraint = dex_configuration_account.get_admin() == admin.key() @ CustomError::InvalidAuthority,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    pub admin: Signer<'info>,
+}
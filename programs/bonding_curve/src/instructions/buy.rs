@@ -4,14 +4,131 @@ use anchor_spl::{
     token::{Mint, Token, TokenAccount},
 };
 
-use crate::{errors::CustomError, state::{CurveConfiguration, LiquidityPool, LiquidityPoolAccount}};
+use crate::{consts::PAUSE_BUYS, errors::CustomError, state::{CurveConfiguration, FeeVault, LiquidityPool, LiquidityPoolAccount, UserPosition}};
 
-pub fn buy(ctx: Context<Buy>, amount: u64, min_output_amount: u64) -> Result<()> {
+pub fn buy(
+    ctx: Context<Buy>,
+    amount: u64,
+    min_output_amount: u64,
+    deadline: Option<i64>,
+    max_price_impact_bps: Option<u16>,
+) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
 
-    if ctx.accounts.dex_configuration_account.get_is_lockdown() == true {
-        return err!(CustomError::Lockdown);
+    let now = Clock::get()?.unix_timestamp;
+    pool.ensure_tradable(&ctx.accounts.dex_configuration_account, PAUSE_BUYS, deadline, now)?;
+    pool.ensure_presale(false, now)?;
+
+    let mut position = ctx.accounts.user_position.as_deref_mut();
+    if let Some(position) = position.as_mut() {
+        position.open(pool.key(), ctx.accounts.user.key(), ctx.bumps.user_position);
+    }
+
+    let price_guard = match max_price_impact_bps {
+        Some(max_price_impact_bps) => Some((pool.spot_price()?, max_price_impact_bps)),
+        None => None,
+    };
+
+    let token_accounts = (
+        &mut *ctx.accounts.output_token_mint,
+        &mut *ctx.accounts.pool_output_token_account,
+        &mut *ctx.accounts.user_output_token_account,
+        &mut *ctx.accounts.input_token_mint,
+        &mut *ctx.accounts.pool_input_token_account,
+        &mut *ctx.accounts.user_input_token_account,
+    );
+
+    pool.buy(
+        token_accounts,
+        &ctx.accounts.dex_configuration_account,
+        ctx.accounts.referrer_token_account.as_deref(),
+        position,
+        amount,
+        min_output_amount,
+        &ctx.accounts.user,
+        &ctx.accounts.token_program,
+    )?;
+    if let Some((price_before, max_price_impact_bps)) = price_guard {
+        pool.ensure_price_impact(price_before, max_price_impact_bps)?;
+    }
+    Ok(())
+}
+
+pub fn buy_exact_out(
+    ctx: Context<Buy>,
+    token_amount: u64,
+    max_input_amount: u64,
+    deadline: Option<i64>,
+    max_price_impact_bps: Option<u16>,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    let now = Clock::get()?.unix_timestamp;
+    pool.ensure_tradable(&ctx.accounts.dex_configuration_account, PAUSE_BUYS, deadline, now)?;
+    pool.ensure_presale(false, now)?;
+
+    let mut position = ctx.accounts.user_position.as_deref_mut();
+    if let Some(position) = position.as_mut() {
+        position.open(pool.key(), ctx.accounts.user.key(), ctx.bumps.user_position);
+    }
+
+    let price_guard = match max_price_impact_bps {
+        Some(max_price_impact_bps) => Some((pool.spot_price()?, max_price_impact_bps)),
+        None => None,
+    };
+
+    let token_accounts = (
+        &mut *ctx.accounts.output_token_mint,
+        &mut *ctx.accounts.pool_output_token_account,
+        &mut *ctx.accounts.user_output_token_account,
+        &mut *ctx.accounts.input_token_mint,
+        &mut *ctx.accounts.pool_input_token_account,
+        &mut *ctx.accounts.user_input_token_account,
+    );
+
+    pool.buy_exact_out(
+        token_accounts,
+        &ctx.accounts.dex_configuration_account,
+        ctx.accounts.referrer_token_account.as_deref(),
+        position,
+        token_amount,
+        max_input_amount,
+        &ctx.accounts.user,
+        &ctx.accounts.token_program,
+    )?;
+    if let Some((price_before, max_price_impact_bps)) = price_guard {
+        pool.ensure_price_impact(price_before, max_price_impact_bps)?;
     }
+    Ok(())
+}
+
+// Buy during the presale window. `allocation` is the wallet's cap in project tokens from
+// the allowlist (0 for none) and `proof` the merkle proof of (user, allocation).
+pub fn presale_buy(
+    ctx: Context<Buy>,
+    amount: u64,
+    min_output_amount: u64,
+    allocation: u64,
+    proof: Vec<[u8; 32]>,
+    deadline: Option<i64>,
+    max_price_impact_bps: Option<u16>,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    let now = Clock::get()?.unix_timestamp;
+    pool.ensure_tradable(&ctx.accounts.dex_configuration_account, PAUSE_BUYS, deadline, now)?;
+    pool.ensure_presale(true, now)?;
+    pool.verify_presale(&ctx.accounts.user.key(), allocation, &proof)?;
+
+    let mut position = ctx.accounts.user_position.as_deref_mut();
+    if let Some(position) = position.as_mut() {
+        position.open(pool.key(), ctx.accounts.user.key(), ctx.bumps.user_position);
+    }
+
+    let price_guard = match max_price_impact_bps {
+        Some(max_price_impact_bps) => Some((pool.spot_price()?, max_price_impact_bps)),
+        None => None,
+    };
 
     let token_accounts = (
         &mut *ctx.accounts.output_token_mint,
@@ -20,17 +137,31 @@ pub fn buy(ctx: Context<Buy>, amount: u64, min_output_amount: u64) -> Result<()>
         &mut *ctx.accounts.input_token_mint,
         &mut *ctx.accounts.pool_input_token_account,
         &mut *ctx.accounts.user_input_token_account,
-        &mut *ctx.accounts.fee_token_collector,
     );
 
     pool.buy(
         token_accounts,
         &ctx.accounts.dex_configuration_account,
+        ctx.accounts.referrer_token_account.as_deref(),
+        position,
         amount,
         min_output_amount,
         &ctx.accounts.user,
         &ctx.accounts.token_program,
     )?;
+    if let Some((price_before, max_price_impact_bps)) = price_guard {
+        pool.ensure_price_impact(price_before, max_price_impact_bps)?;
+    }
+
+    // An active presale made record_buy require the position
+    let tokens_bought = ctx
+        .accounts
+        .user_position
+        .as_ref()
+        .map_or(0, |position| position.tokens_bought);
+    if allocation > 0 && tokens_bought > allocation {
+        return err!(CustomError::PresaleAllocationExceeded);
+    }
     Ok(())
 }
 
@@ -43,12 +174,6 @@ pub struct Buy<'info> {
     )]
     pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
 
-    #[account(
-        mut,
-        constraint = dex_configuration_account.get_fee_collector() == fee_token_collector.key()
-    )]
-    pub fee_token_collector: Box<Account<'info, TokenAccount>>,
-
     #[account(
         mut,
         seeds = [LiquidityPool::POOL_SEED_PREFIX.as_bytes(), output_token_mint.key().as_ref(), input_token_mint.key().as_ref()],
@@ -92,6 +217,29 @@ pub struct Buy<'info> {
     )]
     pub user_input_token_account: Box<Account<'info, TokenAccount>>,
 
+    // Optional referrer paid `referral_fee_bps` of the fee, in the exchange token. Must not
+    // route the referral share back to the trader, the pool or the protocol fee vault
+    #[account(
+        mut,
+        token::mint = input_token_mint,
+        constraint = referrer_token_account.owner != user.key() @ CustomError::InvalidReferrer,
+        constraint = referrer_token_account.key() != pool_input_token_account.key() @ CustomError::InvalidReferrer,
+        constraint = referrer_token_account.owner != pool.key() @ CustomError::InvalidReferrer,
+        constraint = referrer_token_account.owner != FeeVault::address(&input_token_mint.key()) @ CustomError::InvalidReferrer,
+    )]
+    pub referrer_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    // Tracks the wallet's buys while trade limits or a presale apply, see
+    // LiquidityPool::tracks_positions; plain buys leave it out
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserPosition::ACCOUNT_SIZE,
+        seeds = [UserPosition::SEED_PREFIX.as_bytes(), pool.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub user_position: Option<Box<Account<'info, UserPosition>>>,
+
     #[account(mut)]
     pub user: Signer<'info>,
     pub rent: Sysvar<'info, Rent>,
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+use crate::{
+    errors::CustomError,
+    state::{LiquidityPool, LiquidityPoolAccount},
+};
+
+pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    let amount = pool.creator_fee_balance;
+    if amount == 0 {
+        return err!(CustomError::InvalidAmount);
+    }
+
+    pool.transfer_token_from_pool(
+        &ctx.accounts.pool_exchange_token_account,
+        &ctx.accounts.creator_exchange_token_account,
+        amount,
+        &ctx.accounts.token_program,
+    )?;
+    pool.creator_fee_balance = 0;
+
+    emit!(CreatorFeesClaimed {
+        pool: pool.key(),
+        creator: ctx.accounts.creator.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct CreatorFeesClaimed {
+    pub pool: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(
+        mut,
+        seeds = [
+            LiquidityPool::POOL_SEED_PREFIX.as_bytes(),
+            pool.token.as_ref(),
+            exchange_token_mint.key().as_ref()
+        ],
+        bump = pool.bump,
+        has_one = creator @ CustomError::NotCreator,
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    pub exchange_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = exchange_token_mint,
+        associated_token::authority = pool
+    )]
+    pub pool_exchange_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = exchange_token_mint,
+        associated_token::authority = creator,
+    )]
+    pub creator_exchange_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::CustomError,
+    state::{LiquidityPool, UserPosition},
+};
+
+// Returns the rent of the wallet's UserPosition once the pool no longer reads it. A later
+// buy opens a fresh one.
+pub fn close_user_position(ctx: Context<CloseUserPosition>) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+
+    if pool.tracks_positions(Clock::get()?.unix_timestamp) {
+        return err!(CustomError::PositionInUse);
+    }
+
+    emit!(UserPositionClosed {
+        pool: pool.key(),
+        user: ctx.accounts.user.key(),
+        tokens_bought: ctx.accounts.user_position.tokens_bought,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct UserPositionClosed {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub tokens_bought: u64,
+}
+
+#[derive(Accounts)]
+pub struct CloseUserPosition<'info> {
+    #[account(
+        seeds = [
+            LiquidityPool::POOL_SEED_PREFIX.as_bytes(),
+            pool.token.as_ref(),
+            pool.exchange_token.as_ref()
+        ],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [UserPosition::SEED_PREFIX.as_bytes(), pool.key().as_ref(), user.key().as_ref()],
+        bump = user_position.bump,
+    )]
+    pub user_position: Box<Account<'info, UserPosition>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
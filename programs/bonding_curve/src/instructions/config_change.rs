@@ -0,0 +1,122 @@
+use crate::{
+    errors::CustomError,
+    instructions::{FeeRecipientsUpdated, UpdateConfigurationEvent},
+    state::*,
+};
+use anchor_lang::prelude::*;
+
+pub fn queue_config_change(ctx: Context<QueueConfigChange>, change: ConfigChange) -> Result<()> {
+    let dex_config = &mut ctx.accounts.dex_configuration_account;
+    let proposer = ctx.accounts.proposer.key();
+
+    // The fee manager may only queue changes to the fee fields
+    let is_admin = dex_config.get_admin() == proposer;
+    let is_fee_manager = dex_config.get_fee_manager() == proposer;
+    if !(is_admin || (is_fee_manager && change.is_fee_only())) {
+        return err!(CustomError::InvalidAuthority);
+    }
+
+    let pending = dex_config.queue_config_change(change, proposer, Clock::get()?.unix_timestamp)?;
+
+    emit!(ConfigChangeQueuedEvent {
+        proposer,
+        change: pending.change,
+        execute_after: pending.execute_after,
+    });
+
+    Ok(())
+}
+
+// Permissionless once the delay has passed
+pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+    let dex_config = &mut ctx.accounts.dex_configuration_account;
+
+    let previous = dex_config.params();
+    let replaces_fee_recipients = matches!(
+        dex_config.get_pending_change(),
+        Some(pending) if pending.change.fee_recipients.is_some()
+    );
+    dex_config.execute_config_change(Clock::get()?.unix_timestamp)?;
+
+    emit!(UpdateConfigurationEvent {
+        previous,
+        current: dex_config.params(),
+    });
+    if replaces_fee_recipients {
+        emit!(FeeRecipientsUpdated {
+            recipients: dex_config.get_fee_recipients().to_vec(),
+        });
+    }
+
+    Ok(())
+}
+
+pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+    let dex_config = &mut ctx.accounts.dex_configuration_account;
+    let authority = ctx.accounts.authority.key();
+
+    // The admin can drop any queued change, anyone else only their own
+    let proposer = match dex_config.get_pending_change() {
+        Some(pending) => pending.proposer,
+        None => return err!(CustomError::NoPendingConfigChange),
+    };
+    if authority != dex_config.get_admin() && authority != proposer {
+        return err!(CustomError::InvalidAuthority);
+    }
+
+    let pending = dex_config.cancel_config_change()?;
+
+    emit!(ConfigChangeCancelledEvent {
+        authority,
+        change: pending.change,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct ConfigChangeQueuedEvent {
+    pub proposer: Pubkey,
+    pub change: ConfigChange,
+    pub execute_after: i64,
+}
+
+#[event]
+pub struct ConfigChangeCancelledEvent {
+    pub authority: Pubkey,
+    pub change: ConfigChange,
+}
+
+#[derive(Accounts)]
+pub struct QueueConfigChange<'info> {
+    #[account(
+        mut,
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    pub proposer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    #[account(
+        mut,
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelConfigChange<'info> {
+    #[account(
+        mut,
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    pub authority: Signer<'info>,
+}
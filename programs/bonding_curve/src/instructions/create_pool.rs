@@ -1,30 +1,47 @@
-use crate::{errors::CustomError, state::*};
-use anchor_lang::prelude::*;
+use crate::{consts::PAUSE_POOL_CREATION, errors::CustomError, state::*};
+use anchor_lang::{prelude::*, solana_program::program_option::COption};
 use anchor_spl::{
     associated_token::AssociatedToken,
     token::{Mint, Token, TokenAccount},
 };
 
-pub fn create_pool(ctx: Context<CreateLiquidityPool>) -> Result<()> {
+// `open_time` is the unix timestamp trading starts at, 0 to trade as soon as the pool is funded
+pub fn create_pool(
+    ctx: Context<CreateLiquidityPool>,
+    curve_kind: CurveKind,
+    open_time: i64,
+) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
     let dex_configuration_account = &mut ctx.accounts.dex_configuration_account;
 
     if dex_configuration_account.get_exchange_token_mint() != ctx.accounts.exchange_token_mint.key() {
         return err!(CustomError::InvalidExchangeTokenMint);
     }
-    if dex_configuration_account.get_is_lockdown() == true {
+    if dex_configuration_account.is_paused(PAUSE_POOL_CREATION) {
         return err!(CustomError::Lockdown);
     }
+    // Quadratic pools take the proportion in force now and keep it for their whole life
+    let curve_kind = match curve_kind {
+        CurveKind::Quadratic { .. } => CurveKind::Quadratic {
+            proportion: dex_configuration_account.get_proportion(),
+        },
+        other => other,
+    };
+    curve_kind.validate()?;
 
     pool.set_inner(LiquidityPool::new(
         ctx.accounts.payer.key(),
         ctx.accounts.token_mint.key(),
         ctx.accounts.exchange_token_mint.key(),
         ctx.bumps.pool,
+        curve_kind,
+        open_time,
     ));
     emit!(PoolCreated {
         pool: ctx.accounts.pool.key(),
         token_mint: ctx.accounts.token_mint.key(),
+        curve_kind,
+        open_time,
     });
     Ok(())
 }
@@ -33,6 +50,8 @@ pub fn create_pool(ctx: Context<CreateLiquidityPool>) -> Result<()> {
 pub struct PoolCreated {
     pub pool: Pubkey,
     pub token_mint: Pubkey,
+    pub curve_kind: CurveKind,
+    pub open_time: i64,
 }
 
 #[derive(Accounts)]
@@ -53,7 +72,11 @@ pub struct CreateLiquidityPool<'info> {
     )]
     pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
 
-    #[account(mut)]
+    // Only the wallet that created the token through create_token can open its pool
+    #[account(
+        mut,
+        constraint = token_mint.mint_authority == COption::Some(payer.key()) @ CustomError::NotCreator,
+    )]
     pub token_mint: Box<Account<'info, Mint>>,
 
     #[account(mut)]
@@ -6,8 +6,9 @@ use anchor_spl::metadata::{
 };
 use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
 
+use crate::consts::PAUSE_CREATION;
 use crate::errors::CustomError;
-use crate::state::CurveConfiguration;
+use crate::state::{CurveConfiguration, FeeVault, SolFeeVault};
 
 pub fn create_token(
     ctx: Context<CreateToken>,
@@ -21,40 +22,52 @@ pub fn create_token(
     let off_chain_id_ref = &off_chain_id;
     let uri_ref = &uri;
 
-    if ctx.accounts.dex_configuration_account.get_is_lockdown() == true {
+    if ctx.accounts.dex_configuration_account.is_paused(PAUSE_CREATION) {
         return err!(CustomError::Lockdown);
     }
 
     msg!("Creating token");
-    msg!(
-        "Fee sol collector: {}",
-        ctx.accounts.fee_sol_collector.key()
-    );
-    msg!("Fee collector: {}", ctx.accounts.fee_collector.key());
 
-    if ctx.accounts.dex_configuration_account.get_is_sol_fee() == true {
+    let creation_fees = ctx.accounts.dex_configuration_account.get_creation_fees();
+    if ctx.accounts.dex_configuration_account.get_is_sol_fee() {
+        let sol_fee_vault = ctx
+            .accounts
+            .sol_fee_vault
+            .as_deref_mut()
+            .ok_or(CustomError::FeeVaultRequired)?;
         system_program::transfer(
             CpiContext::new(
                 ctx.accounts.system_program.to_account_info(),
                 system_program::Transfer {
                     from: ctx.accounts.user.to_account_info(),
-                    to: ctx.accounts.fee_sol_collector.to_account_info(),
+                    to: sol_fee_vault.to_account_info(),
                 },
             ),
-            ctx.accounts.dex_configuration_account.get_creation_fees(),
+            creation_fees,
         )?;
+        sol_fee_vault.bump = ctx.bumps.sol_fee_vault;
+        sol_fee_vault.record_accrual(creation_fees)?;
     } else {
+        let (Some(fee_vault), Some(fee_vault_token_account)) = (
+            ctx.accounts.fee_vault.as_deref_mut(),
+            ctx.accounts.fee_vault_token_account.as_deref(),
+        ) else {
+            return err!(CustomError::FeeVaultRequired);
+        };
         token::transfer(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
                 token::Transfer {
                     from: ctx.accounts.user_fee_token_account.to_account_info(),
-                    to: ctx.accounts.fee_collector.to_account_info(),
+                    to: fee_vault_token_account.to_account_info(),
                     authority: ctx.accounts.user.to_account_info(),
                 }
             ),
-            ctx.accounts.dex_configuration_account.get_creation_fees(),
+            creation_fees,
         )?;
+        fee_vault.exchange_token_mint = ctx.accounts.exchange_token_mint.key();
+        fee_vault.bump = ctx.bumps.fee_vault;
+        fee_vault.record_accrual(creation_fees)?;
     }
 
     let decimals: u8 = 9;
@@ -144,6 +157,7 @@ pub struct CreateToken<'info> {
     // Thêm token account của user cho token fee
     #[account(
     mut,
+    token::mint = exchange_token_mint,
     constraint = user_fee_token_account.owner == user.key()
     )]
     pub user_fee_token_account: Account<'info, TokenAccount>,
@@ -167,19 +181,40 @@ pub struct CreateToken<'info> {
     )]
     pub dex_configuration_account: Account<'info, CurveConfiguration>,
 
-    /// CHECK:
     #[account(
-        mut,
-        constraint = dex_configuration_account.get_fee_sol_collector() == fee_sol_collector.key()
+        constraint = dex_configuration_account.get_exchange_token_mint() == exchange_token_mint.key()
     )]
-    pub fee_sol_collector: AccountInfo<'info>,
+    pub exchange_token_mint: Box<Account<'info, Mint>>,
 
-    /// CHECK:
+    // Creation fees paid in SOL accrue here. Only needed, and only created, while
+    // the config takes creation fees in SOL
     #[account(
-        mut,
-        constraint = dex_configuration_account.get_fee_collector() == fee_collector.key()
+        init_if_needed,
+        payer = user,
+        space = SolFeeVault::ACCOUNT_SIZE,
+        seeds = [SolFeeVault::SEED.as_bytes()],
+        bump,
+    )]
+    pub sol_fee_vault: Option<Box<Account<'info, SolFeeVault>>>,
+
+    // Creation fees paid in the exchange token accrue in this vault's token account.
+    // Both are left out while the config takes creation fees in SOL
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = FeeVault::ACCOUNT_SIZE,
+        seeds = [FeeVault::SEED.as_bytes(), exchange_token_mint.key().as_ref()],
+        bump,
+    )]
+    pub fee_vault: Option<Box<Account<'info, FeeVault>>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = exchange_token_mint,
+        associated_token::authority = fee_vault,
     )]
-    pub fee_collector: Account<'info, TokenAccount>,
+    pub fee_vault_token_account: Option<Box<Account<'info, TokenAccount>>>,
 
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
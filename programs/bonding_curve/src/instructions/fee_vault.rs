@@ -0,0 +1,364 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+
+use crate::{
+    errors::CustomError,
+    state::{
+        CurveConfiguration, FeeRecipient, FeeVault, LiquidityPool, LiquidityPoolAccount,
+        SolFeeVault,
+    },
+    utils::fee_share,
+};
+
+// Moves a pool's accrued protocol fees into the FeeVault of its exchange mint. Anyone can
+// crank it, the funds only ever move between two program-owned accounts.
+pub fn sweep_fees(ctx: Context<SweepFees>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    let amount = pool.protocol_fee_balance;
+    if amount == 0 {
+        return err!(CustomError::InvalidAmount);
+    }
+
+    pool.transfer_token_from_pool(
+        &ctx.accounts.pool_exchange_token_account,
+        &ctx.accounts.fee_vault_token_account,
+        amount,
+        &ctx.accounts.token_program,
+    )?;
+    pool.protocol_fee_balance = 0;
+
+    let fee_vault = &mut ctx.accounts.fee_vault;
+    fee_vault.exchange_token_mint = ctx.accounts.exchange_token_mint.key();
+    fee_vault.bump = ctx.bumps.fee_vault;
+    fee_vault.record_accrual(amount)?;
+
+    emit!(FeesSwept {
+        pool: pool.key(),
+        exchange_token_mint: fee_vault.exchange_token_mint,
+        amount,
+        total_accrued: fee_vault.total_accrued,
+    });
+
+    Ok(())
+}
+
+pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+    if amount == 0 || amount > ctx.accounts.fee_vault_token_account.amount {
+        return err!(CustomError::InvalidAmount);
+    }
+
+    let exchange_token_mint = ctx.accounts.exchange_token_mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        FeeVault::SEED.as_bytes(),
+        exchange_token_mint.as_ref(),
+        &[ctx.accounts.fee_vault.bump],
+    ]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.fee_vault_token_account.to_account_info(),
+                to: ctx.accounts.fee_collector.to_account_info(),
+                authority: ctx.accounts.fee_vault.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    let fee_vault = &mut ctx.accounts.fee_vault;
+    fee_vault.record_withdrawal(amount)?;
+
+    emit!(FeesWithdrawn {
+        exchange_token_mint,
+        recipient: ctx.accounts.fee_collector.key(),
+        amount,
+        total_withdrawn: fee_vault.total_withdrawn,
+    });
+
+    Ok(())
+}
+
+// Splits the vault's whole balance over the fee distribution table. Anyone can crank it;
+// remaining_accounts are the recipients' exchange token accounts, in table order.
+pub fn distribute_fees<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeFees<'info>>,
+) -> Result<()> {
+    let recipients = ctx.accounts.dex_configuration_account.get_fee_recipients();
+    if recipients.is_empty() {
+        return err!(CustomError::NoFeeRecipients);
+    }
+    if ctx.remaining_accounts.len() != recipients.len() {
+        return err!(CustomError::InvalidFeeRecipients);
+    }
+
+    let total = ctx.accounts.fee_vault_token_account.amount;
+    if total == 0 {
+        return err!(CustomError::InvalidAmount);
+    }
+
+    let exchange_token_mint = ctx.accounts.exchange_token_mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        FeeVault::SEED.as_bytes(),
+        exchange_token_mint.as_ref(),
+        &[ctx.accounts.fee_vault.bump],
+    ]];
+
+    let mut amounts = Vec::with_capacity(recipients.len());
+    let mut distributed: u64 = 0;
+    for (index, (recipient, account_info)) in recipients
+        .iter()
+        .zip(ctx.remaining_accounts.iter())
+        .enumerate()
+    {
+        let recipient_account = Account::<TokenAccount>::try_from(account_info)?;
+        if recipient_account.owner != recipient.owner
+            || recipient_account.mint != exchange_token_mint
+        {
+            return err!(CustomError::InvalidFeeRecipients);
+        }
+
+        // The last recipient also takes the rounding dust
+        let amount = if index == recipients.len() - 1 {
+            total - distributed
+        } else {
+            fee_share(total, recipient.weight_bps as u64)?
+        };
+        if amount > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: ctx.accounts.fee_vault_token_account.to_account_info(),
+                        to: account_info.clone(),
+                        authority: ctx.accounts.fee_vault.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+            )?;
+        }
+        distributed += amount;
+        amounts.push(amount);
+    }
+
+    let fee_vault = &mut ctx.accounts.fee_vault;
+    fee_vault.record_withdrawal(total)?;
+
+    emit!(FeesDistributed {
+        exchange_token_mint,
+        recipients: recipients.iter().map(|recipient| recipient.owner).collect(),
+        amounts,
+        total_withdrawn: fee_vault.total_withdrawn,
+    });
+
+    Ok(())
+}
+
+pub fn withdraw_sol_fees(ctx: Context<WithdrawSolFees>, amount: u64) -> Result<()> {
+    let vault_info = ctx.accounts.sol_fee_vault.to_account_info();
+    // The vault keeps its rent-exempt minimum so it survives the withdrawal
+    let available = vault_info
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(vault_info.data_len()));
+    if amount == 0 || amount > available {
+        return err!(CustomError::InvalidAmount);
+    }
+
+    // The vault is owned by this program, so lamports move without a system transfer
+    **vault_info.try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.fee_sol_collector.try_borrow_mut_lamports()? += amount;
+
+    let sol_fee_vault = &mut ctx.accounts.sol_fee_vault;
+    sol_fee_vault.record_withdrawal(amount)?;
+
+    emit!(SolFeesWithdrawn {
+        recipient: ctx.accounts.fee_sol_collector.key(),
+        amount,
+        total_withdrawn: sol_fee_vault.total_withdrawn,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct FeesSwept {
+    pub pool: Pubkey,
+    pub exchange_token_mint: Pubkey,
+    pub amount: u64,
+    pub total_accrued: u64,
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub exchange_token_mint: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub total_withdrawn: u64,
+}
+
+#[event]
+pub struct FeeRecipientsUpdated {
+    pub recipients: Vec<FeeRecipient>,
+}
+
+#[event]
+pub struct FeesDistributed {
+    pub exchange_token_mint: Pubkey,
+    pub recipients: Vec<Pubkey>,
+    pub amounts: Vec<u64>,
+    pub total_withdrawn: u64,
+}
+
+#[event]
+pub struct SolFeesWithdrawn {
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub total_withdrawn: u64,
+}
+
+#[derive(Accounts)]
+pub struct SweepFees<'info> {
+    #[account(
+        mut,
+        seeds = [
+            LiquidityPool::POOL_SEED_PREFIX.as_bytes(),
+            pool.token.as_ref(),
+            exchange_token_mint.key().as_ref()
+        ],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    pub exchange_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = exchange_token_mint,
+        associated_token::authority = pool
+    )]
+    pub pool_exchange_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = FeeVault::ACCOUNT_SIZE,
+        seeds = [FeeVault::SEED.as_bytes(), exchange_token_mint.key().as_ref()],
+        bump,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = exchange_token_mint,
+        associated_token::authority = fee_vault,
+    )]
+    pub fee_vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+        constraint = dex_configuration_account.get_admin() == authority.key()
+            || dex_configuration_account.get_fee_manager() == authority.key()
+            @ CustomError::InvalidAuthority,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    pub exchange_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [FeeVault::SEED.as_bytes(), exchange_token_mint.key().as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(
+        mut,
+        associated_token::mint = exchange_token_mint,
+        associated_token::authority = fee_vault,
+    )]
+    pub fee_vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = exchange_token_mint,
+        constraint = dex_configuration_account.get_fee_collector() == fee_collector.key()
+            @ CustomError::InvalidAuthority,
+    )]
+    pub fee_collector: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct DistributeFees<'info> {
+    #[account(
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    pub exchange_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [FeeVault::SEED.as_bytes(), exchange_token_mint.key().as_ref()],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(
+        mut,
+        associated_token::mint = exchange_token_mint,
+        associated_token::authority = fee_vault,
+    )]
+    pub fee_vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawSolFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+        constraint = dex_configuration_account.get_admin() == authority.key()
+            || dex_configuration_account.get_fee_manager() == authority.key()
+            @ CustomError::InvalidAuthority,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    #[account(
+        mut,
+        seeds = [SolFeeVault::SEED.as_bytes()],
+        bump = sol_fee_vault.bump,
+    )]
+    pub sol_fee_vault: Box<Account<'info, SolFeeVault>>,
+
+    /// CHECK: only receives lamports, must match the configured SOL fee collector
+    #[account(
+        mut,
+        constraint = dex_configuration_account.get_fee_sol_collector() == fee_sol_collector.key()
+            @ CustomError::InvalidAuthority,
+    )]
+    pub fee_sol_collector: UncheckedAccount<'info>,
+}
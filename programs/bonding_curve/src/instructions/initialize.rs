@@ -1,24 +1,29 @@
-use crate::{errors::CustomError, state::*};
+use crate::{consts::PAUSE_ALL, errors::CustomError, state::*};
 use anchor_lang::prelude::*;
 
+#[allow(clippy::too_many_arguments)]
 pub fn initialize(
     ctx: Context<InitializeCurveConfiguration>,
     fee_percentage: u64,
     creation_fees: u64,
-    proportion: f64,
+    proportion: u64,
     fee_collector: Pubkey,
     fee_sol_collector: Pubkey,
     exchange_token_mint: Pubkey,
     initial_token_for_pool: u64,
     is_sol_fee: bool,
-    is_lockdown: bool,
+    pause_flags: u8,
 ) -> Result<()> {
     let dex_config = &mut ctx.accounts.dex_configuration_account;
 
-    if fee_percentage < 1 || fee_percentage > 10000 {
+    if !(1..=10000).contains(&fee_percentage) {
         return err!(CustomError::InvalidFee);
     }
 
+    if proportion == 0 || pause_flags & !PAUSE_ALL != 0 {
+        return err!(CustomError::InvalidInput);
+    }
+
     dex_config.set_inner(CurveConfiguration::new(
         fee_percentage,
         creation_fees,
@@ -29,11 +34,11 @@ pub fn initialize(
         ctx.accounts.admin.key(),
         initial_token_for_pool,
         is_sol_fee,
-        is_lockdown,
+        pause_flags,
     ));
 
     emit!(CreateConfigurationEvent {
-        fee_percentage: fee_percentage as u64,
+        fee_percentage,
         creation_fees,
         proportion,
         initial_token_for_pool,
@@ -41,48 +46,49 @@ pub fn initialize(
         fee_sol_collector,
         exchange_token_mint,
         is_sol_fee,
-        is_lockdown,
+        pause_flags,
     });
 
     Ok(())
 }
 
-pub fn update_configuration(
-    ctx: Context<UpdateConfiguration>,
-    fee_percentage: u64,
-    creation_fees: u64,
-    proportion: f64,
-    fee_collector: Pubkey,
-    fee_sol_collector: Pubkey,
-    exchange_token_mint: Pubkey,
-    initial_token_for_pool: u64,
-    is_sol_fee: bool,
-    is_lockdown: bool,
-) -> Result<()> {
+pub fn update_configuration(ctx: Context<UpdateConfiguration>, update: ConfigUpdate) -> Result<()> {
     let dex_config = &mut ctx.accounts.dex_configuration_account;
 
-    dex_config.update_configuration(
-        fee_percentage,
-        creation_fees,
-        proportion,
-        fee_collector,
-        fee_sol_collector,
-        exchange_token_mint,
-        initial_token_for_pool,
-        is_sol_fee,
-        is_lockdown,
-    )?;
+    let previous = dex_config.params();
+    dex_config.update_configuration(update)?;
 
     emit!(UpdateConfigurationEvent {
-        fee_percentage,
-        creation_fees,
-        proportion,
-        initial_token_for_pool,
-        fee_collector,
-        fee_sol_collector,
-        exchange_token_mint,
-        is_sol_fee,
-        is_lockdown,
+        previous,
+        current: dex_config.params(),
+    });
+
+    Ok(())
+}
+
+pub fn update_fees(ctx: Context<UpdateFees>, update: FeeUpdate) -> Result<()> {
+    let dex_config = &mut ctx.accounts.dex_configuration_account;
+
+    let previous = dex_config.params();
+    dex_config.update_fees(update)?;
+
+    emit!(UpdateConfigurationEvent {
+        previous,
+        current: dex_config.params(),
+    });
+
+    Ok(())
+}
+
+pub fn set_pause_flags(ctx: Context<SetPauseFlags>, pause_flags: u8) -> Result<()> {
+    let dex_config = &mut ctx.accounts.dex_configuration_account;
+
+    let previous = dex_config.params();
+    dex_config.set_pause_flags(pause_flags)?;
+
+    emit!(UpdateConfigurationEvent {
+        previous,
+        current: dex_config.params(),
     });
 
     Ok(())
@@ -90,28 +96,21 @@ pub fn update_configuration(
 
 #[event]
 pub struct UpdateConfigurationEvent {
-    pub fee_percentage: u64,
-    pub creation_fees: u64,
-    pub proportion: f64,
-    pub initial_token_for_pool: u64,
-    pub fee_collector: Pubkey,
-    pub fee_sol_collector: Pubkey,
-    pub exchange_token_mint: Pubkey,
-    pub is_sol_fee: bool,
-    pub is_lockdown: bool,
+    pub previous: ConfigurationParams,
+    pub current: ConfigurationParams,
 }
 
 #[event]
 pub struct CreateConfigurationEvent {
     pub fee_percentage: u64,
     pub creation_fees: u64,
-    pub proportion: f64,
+    pub proportion: u64,
     pub initial_token_for_pool: u64,
     pub fee_collector: Pubkey,
     pub fee_sol_collector: Pubkey,
     pub exchange_token_mint: Pubkey,
     pub is_sol_fee: bool,
-    pub is_lockdown: bool,
+    pub pause_flags: u8,
 }
 
 #[derive(Accounts)]
@@ -136,6 +135,40 @@ pub struct UpdateConfiguration<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+        constraint = dex_configuration_account.get_admin() == admin.key() @ CustomError::InvalidAuthority,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateFees<'info> {
+    pub fee_manager: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+        constraint = dex_configuration_account.get_fee_manager() == fee_manager.key() @ CustomError::InvalidAuthority,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+}
+
+#[derive(Accounts)]
+pub struct SetPauseFlags<'info> {
+    // Either the admin or the pause guardian
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+        constraint = dex_configuration_account.get_admin() == authority.key()
+            || dex_configuration_account.get_pause_guardian() == authority.key()
+            @ CustomError::InvalidAuthority,
+    )]
     pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
 }
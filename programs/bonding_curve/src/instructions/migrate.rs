@@ -0,0 +1,302 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, accessor, Burn, Mint, Token, TokenAccount},
+};
+use raydium_cp_swap::{cpi, program::RaydiumCpSwap, states::AmmConfig};
+
+use crate::{
+    consts::PAUSE_MIGRATION,
+    errors::CustomError,
+    state::{
+        CurveConfiguration, LiquidityBurned, LiquidityPool, LiquidityPoolAccount, PoolStatus,
+        RaydiumPoolCreated,
+    },
+};
+
+#[derive(Accounts)]
+pub struct Migrate<'info> {
+    #[account(
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+        constraint = dex_configuration_account.get_migration_operator() == payer.key() @ CustomError::InvalidAuthority,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    #[account(
+        mut,
+        seeds = [
+            LiquidityPool::POOL_SEED_PREFIX.as_bytes(),
+            token_mint.key().as_ref(),
+            exchange_token_mint.key().as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    /// CHECK: data-less PDA of the pool that acts as the Raydium pool creator. The pool
+    /// account itself carries data, so it cannot fund cp-swap's system-program allocations.
+    #[account(
+        mut,
+        seeds = [LiquidityPool::MIGRATION_AUTHORITY_SEED.as_bytes(), pool.key().as_ref()],
+        bump,
+    )]
+    pub migration_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub exchange_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = pool
+    )]
+    pub pool_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = exchange_token_mint,
+        associated_token::authority = pool
+    )]
+    pub pool_exchange_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = token_mint,
+        associated_token::authority = migration_authority
+    )]
+    pub authority_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = exchange_token_mint,
+        associated_token::authority = migration_authority
+    )]
+    pub authority_exchange_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub cp_swap_program: Program<'info, RaydiumCpSwap>,
+
+    /// Which config the pool belongs to.
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+
+    /// CHECK: Initialize an account to store the pool state, init by cp-swap
+    #[account(mut)]
+    pub pool_state: UncheckedAccount<'info>,
+
+    /// CHECK: pool lp mint, init by cp-swap
+    #[account(mut)]
+    pub lp_mint: UncheckedAccount<'info>,
+
+    /// CHECK: migration authority lp ATA token account, init by cp-swap
+    #[account(mut)]
+    pub creator_lp_token: UncheckedAccount<'info>,
+
+    /// CHECK: Token_0 vault for the pool, init by cp-swap
+    #[account(mut)]
+    pub token_0_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Token_1 vault for the pool, init by cp-swap
+    #[account(mut)]
+    pub token_1_vault: UncheckedAccount<'info>,
+
+    /// CHECK: create pool fee account, checked by cp-swap
+    #[account(mut)]
+    pub create_pool_fee: UncheckedAccount<'info>,
+
+    /// CHECK: an account to store oracle observations, init by cp-swap
+    #[account(mut)]
+    pub observation_state: UncheckedAccount<'info>,
+
+    /// Pays for the authority token accounts and lends the lamports cp-swap needs. Must be
+    /// the migration operator, who also picks the amm_config fee tier
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Moves a completed pool's vaults into a new Raydium CP-Swap pool. The migration authority
+// PDA signs as the cp-swap creator, so the liquidity never passes through a user wallet.
+// Only the migration operator runs it, and the Raydium pool opens as soon as it exists.
+// `funding_lamports` covers cp-swap's rent and pool creation fee; the rest is refunded.
+pub fn migrate(ctx: Context<Migrate>, funding_lamports: u64) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    if ctx
+        .accounts
+        .dex_configuration_account
+        .is_paused(PAUSE_MIGRATION)
+    {
+        return err!(CustomError::Lockdown);
+    }
+
+    match pool.status {
+        PoolStatus::Completed => {}
+        PoolStatus::Migrated => return err!(CustomError::AlreadyMigrated),
+        _ => return err!(CustomError::CurveNotComplete),
+    }
+
+    let token_amount = ctx.accounts.pool_token_account.amount;
+    // Unclaimed creator and protocol fees stay behind in the vault
+    let exchange_amount = ctx
+        .accounts
+        .pool_exchange_token_account
+        .amount
+        .checked_sub(pool.unclaimed_fees()?)
+        .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+
+    pool.transfer_token_from_pool(
+        &ctx.accounts.pool_token_account,
+        &ctx.accounts.authority_token_account,
+        token_amount,
+        &ctx.accounts.token_program,
+    )?;
+    pool.transfer_token_from_pool(
+        &ctx.accounts.pool_exchange_token_account,
+        &ctx.accounts.authority_exchange_token_account,
+        exchange_amount,
+        &ctx.accounts.token_program,
+    )?;
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.payer.to_account_info(),
+                to: ctx.accounts.migration_authority.to_account_info(),
+            },
+        ),
+        funding_lamports,
+    )?;
+
+    let pool_key = pool.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        LiquidityPool::MIGRATION_AUTHORITY_SEED.as_bytes(),
+        pool_key.as_ref(),
+        &[ctx.bumps.migration_authority],
+    ]];
+
+    // cp-swap requires token_0_mint < token_1_mint
+    let token_is_0 = ctx.accounts.token_mint.key() < ctx.accounts.exchange_token_mint.key();
+    let (
+        token_0_mint,
+        token_1_mint,
+        creator_token_0,
+        creator_token_1,
+        init_amount_0,
+        init_amount_1,
+    ) = if token_is_0 {
+        (
+            ctx.accounts.token_mint.to_account_info(),
+            ctx.accounts.exchange_token_mint.to_account_info(),
+            ctx.accounts.authority_token_account.to_account_info(),
+            ctx.accounts
+                .authority_exchange_token_account
+                .to_account_info(),
+            token_amount,
+            exchange_amount,
+        )
+    } else {
+        (
+            ctx.accounts.exchange_token_mint.to_account_info(),
+            ctx.accounts.token_mint.to_account_info(),
+            ctx.accounts
+                .authority_exchange_token_account
+                .to_account_info(),
+            ctx.accounts.authority_token_account.to_account_info(),
+            exchange_amount,
+            token_amount,
+        )
+    };
+
+    let cpi_accounts = cpi::accounts::Initialize {
+        creator: ctx.accounts.migration_authority.to_account_info(),
+        amm_config: ctx.accounts.amm_config.to_account_info(),
+        authority: ctx.accounts.authority.to_account_info(),
+        pool_state: ctx.accounts.pool_state.to_account_info(),
+        token_0_mint,
+        token_1_mint,
+        lp_mint: ctx.accounts.lp_mint.to_account_info(),
+        creator_token_0,
+        creator_token_1,
+        creator_lp_token: ctx.accounts.creator_lp_token.to_account_info(),
+        token_0_vault: ctx.accounts.token_0_vault.to_account_info(),
+        token_1_vault: ctx.accounts.token_1_vault.to_account_info(),
+        create_pool_fee: ctx.accounts.create_pool_fee.to_account_info(),
+        observation_state: ctx.accounts.observation_state.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        token_0_program: ctx.accounts.token_program.to_account_info(),
+        token_1_program: ctx.accounts.token_program.to_account_info(),
+        associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+    };
+    let cpi_context = CpiContext::new_with_signer(
+        ctx.accounts.cp_swap_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    let open_time = Clock::get()?.unix_timestamp as u64;
+    cpi::initialize(cpi_context, init_amount_0, init_amount_1, open_time)?;
+
+    // Burn every LP token so the graduated liquidity can never be pulled
+    let lp_amount = accessor::amount(&ctx.accounts.creator_lp_token.to_account_info())?;
+    token::burn(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                from: ctx.accounts.creator_lp_token.to_account_info(),
+                authority: ctx.accounts.migration_authority.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        lp_amount,
+    )?;
+
+    // Hand back whatever cp-swap did not spend
+    let leftover = ctx.accounts.migration_authority.lamports();
+    if leftover > 0 {
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.migration_authority.to_account_info(),
+                    to: ctx.accounts.payer.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            leftover,
+        )?;
+    }
+
+    pool.update_reserves(0, 0)?;
+    pool.set_status(PoolStatus::Migrated)?;
+
+    emit!(RaydiumPoolCreated {
+        bonding_pool: pool.key(),
+        raydium_pool: ctx.accounts.pool_state.key(),
+        token_mint: ctx.accounts.token_mint.key(),
+        exchange_token_mint: ctx.accounts.exchange_token_mint.key(),
+        creator: ctx.accounts.migration_authority.key(),
+    });
+
+    emit!(LiquidityBurned {
+        raydium_pool: ctx.accounts.pool_state.key(),
+        lp_mint: ctx.accounts.lp_mint.key(),
+        amount: lp_amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use crate::{
+    errors::CustomError,
+    state::{CurveConfiguration, LiquidityPool},
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::Mint;
+
+pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+    let account = ctx.accounts.dex_configuration_account.to_account_info();
+
+    resize_account(
+        &account,
+        &ctx.accounts.admin,
+        &ctx.accounts.system_program,
+        CurveConfiguration::ACCOUNT_SIZE,
+    )?;
+
+    let mut config = CurveConfiguration::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    if config.get_admin() != ctx.accounts.admin.key() {
+        return err!(CustomError::InvalidAuthority);
+    }
+    let from_version = config.upgrade()?;
+    config.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+
+    emit!(AccountMigratedEvent {
+        account: account.key(),
+        from_version,
+        to_version: CurveConfiguration::VERSION,
+    });
+
+    Ok(())
+}
+
+// Permissionless: the upgrade is deterministic, the caller only pays the extra rent
+pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+    let account = ctx.accounts.pool.to_account_info();
+
+    resize_account(
+        &account,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        LiquidityPool::ACCOUNT_SIZE,
+    )?;
+
+    let mut pool = LiquidityPool::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    let from_version = pool.upgrade(&ctx.accounts.dex_configuration_account)?;
+    pool.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+
+    emit!(AccountMigratedEvent {
+        account: account.key(),
+        from_version,
+        to_version: LiquidityPool::VERSION,
+    });
+
+    Ok(())
+}
+
+// Grows `account` to `new_len` with zeroed bytes, topping up rent from `payer`
+fn resize_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    new_len: usize,
+) -> Result<()> {
+    if account.data_len() >= new_len {
+        return Ok(());
+    }
+
+    let rent_due = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+
+    account.realloc(new_len, true)?;
+    Ok(())
+}
+
+#[event]
+pub struct AccountMigratedEvent {
+    pub account: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// CHECK: may still hold an older layout, so it is deserialized by hand after the realloc
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    /// CHECK: may still hold an older layout, so it is deserialized by hand after the realloc
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [
+            LiquidityPool::POOL_SEED_PREFIX.as_bytes(),
+            token_mint.key().as_ref(),
+            exchange_token_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub pool: UncheckedAccount<'info>,
+
+    // Supplies the proportion that v0 pools priced on
+    #[account(
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    pub exchange_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
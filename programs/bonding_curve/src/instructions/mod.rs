@@ -1,17 +1,37 @@
 pub mod add_liquidity;
 pub mod create_pool;
 pub mod initialize;
-pub mod remove_liquidity;
 pub mod buy;
 pub mod sell;
 pub mod create_token;
 pub mod proxy_initialize;
+pub mod quote;
+pub mod migrate;
+pub mod admin;
+pub mod config_change;
+pub mod migrate_account;
+pub mod set_pool_halt;
+pub mod claim_creator_fees;
+pub mod fee_vault;
+pub mod set_trade_limits;
+pub mod set_presale;
+pub mod close_user_position;
 
 pub use add_liquidity::*;
 pub use create_pool::*;
 pub use initialize::*;
-pub use remove_liquidity::*;
 pub use buy::*;
 pub use sell::*;
 pub use create_token::*;
-pub use proxy_initialize::*;
\ No newline at end of file
+pub use proxy_initialize::*;
+pub use quote::*;
+pub use migrate::*;
+pub use admin::*;
+pub use config_change::*;
+pub use migrate_account::*;
+pub use set_pool_halt::*;
+pub use claim_creator_fees::*;
+pub use fee_vault::*;
+pub use set_trade_limits::*;
+pub use set_presale::*;
+pub use close_user_position::*;
\ No newline at end of file
@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::Token,
+    token::{self, accessor, Burn, Token},
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
 use raydium_cp_swap::{
@@ -10,13 +10,26 @@ use raydium_cp_swap::{
     states::AmmConfig,
 };
 
+use crate::{
+    consts::PAUSE_MIGRATION,
+    errors::CustomError,
+    state::{CurveConfiguration, LiquidityBurned},
+};
+
 #[derive(Accounts)]
 pub struct ProxyInitialize<'info> {
     pub cp_swap_program: Program<'info, RaydiumCpSwap>,
-    /// Address paying to create the pool. Can be anyone
+    /// Address paying to create the pool. Must be the migration operator
     #[account(mut)]
     pub creator: Signer<'info>,
 
+    #[account(
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+        constraint = curve_config.get_migration_operator() == creator.key() @ CustomError::InvalidAuthority,
+    )]
+    pub curve_config: Box<Account<'info, CurveConfiguration>>,
+
     /// Which config the pool belongs to.
     pub amm_config: Box<Account<'info, AmmConfig>>,
 
@@ -84,13 +97,18 @@ pub struct ProxyInitialize<'info> {
     pub system_program: Program<'info, System>,
 }
 
+// Opens a Raydium pool funded from the operator's own token accounts. Bonding pools never
+// go through here, they graduate with migrate and their vaults never leave program custody.
 pub fn proxy_initialize(
     ctx: Context<ProxyInitialize>,
     init_amount_0: u64,
     init_amount_1: u64,
     open_time: u64,
 ) -> Result<()> {
-    msg!("Here");
+    if ctx.accounts.curve_config.is_paused(PAUSE_MIGRATION) {
+        return err!(CustomError::Lockdown);
+    }
+
     let cpi_accounts = cpi::accounts::Initialize {
         creator: ctx.accounts.creator.to_account_info(),
         amm_config: ctx.accounts.amm_config.to_account_info(),
@@ -114,5 +132,25 @@ pub fn proxy_initialize(
         rent: ctx.accounts.rent.to_account_info(),
     };
     let cpi_context = CpiContext::new(ctx.accounts.cp_swap_program.to_account_info(), cpi_accounts);
-    cpi::initialize(cpi_context, init_amount_0, init_amount_1, open_time)
+    cpi::initialize(cpi_context, init_amount_0, init_amount_1, open_time)?;
+
+    // Burn the creator's LP tokens so the liquidity is locked for good
+    let lp_amount = accessor::amount(&ctx.accounts.creator_lp_token.to_account_info())?;
+    let burn_context = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Burn {
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            from: ctx.accounts.creator_lp_token.to_account_info(),
+            authority: ctx.accounts.creator.to_account_info(),
+        },
+    );
+    token::burn(burn_context, lp_amount)?;
+
+    emit!(LiquidityBurned {
+        raydium_pool: ctx.accounts.pool_state.key(),
+        lp_mint: ctx.accounts.lp_mint.key(),
+        amount: lp_amount,
+    });
+
+    Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,44 @@
+use anchor_lang::{prelude::*, solana_program::program::set_return_data};
+
+use crate::state::{CurveConfiguration, LiquidityPool};
+
+pub fn quote_buy(ctx: Context<QuotePool>, amount: u64) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let curve_config = &ctx.accounts.dex_configuration_account;
+
+    let swap = pool.quote_buy(curve_config, amount)?;
+    let quote = pool.quote_after(swap, true)?;
+
+    set_return_data(&quote.try_to_vec()?);
+    Ok(())
+}
+
+pub fn quote_sell(ctx: Context<QuotePool>, amount: u64) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let curve_config = &ctx.accounts.dex_configuration_account;
+
+    let swap = pool.quote_sell(curve_config, amount)?;
+    let quote = pool.quote_after(swap, false)?;
+
+    set_return_data(&quote.try_to_vec()?);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct QuotePool<'info> {
+    #[account(
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    #[account(
+        seeds = [
+            LiquidityPool::POOL_SEED_PREFIX.as_bytes(),
+            pool.token.as_ref(),
+            pool.exchange_token.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+}
@@ -4,14 +4,24 @@ use anchor_spl::{
     token::{Mint, Token, TokenAccount},
 };
 
-use crate::{errors::CustomError, state::{CurveConfiguration, LiquidityPool, LiquidityPoolAccount}};
+use crate::{consts::PAUSE_SELLS, errors::CustomError, state::{CurveConfiguration, FeeVault, LiquidityPool, LiquidityPoolAccount}};
 
-pub fn sell(ctx: Context<Sell>, amount: u64, min_output_amount: u64) -> Result<()> {
+pub fn sell(
+    ctx: Context<Sell>,
+    amount: u64,
+    min_output_amount: u64,
+    deadline: Option<i64>,
+    max_price_impact_bps: Option<u16>,
+) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
 
-    if ctx.accounts.dex_configuration_account.get_is_lockdown() == true {
-        return err!(CustomError::Lockdown);
-    }
+    let now = Clock::get()?.unix_timestamp;
+    pool.ensure_tradable(&ctx.accounts.dex_configuration_account, PAUSE_SELLS, deadline, now)?;
+
+    let price_guard = match max_price_impact_bps {
+        Some(max_price_impact_bps) => Some((pool.spot_price()?, max_price_impact_bps)),
+        None => None,
+    };
 
     let token_accounts = (
         &mut *ctx.accounts.token_mint,
@@ -20,17 +30,61 @@ pub fn sell(ctx: Context<Sell>, amount: u64, min_output_amount: u64) -> Result<(
         &mut *ctx.accounts.exchange_token_mint,
         &mut *ctx.accounts.pool_exchange_token_account,
         &mut *ctx.accounts.user_exchange_token_account,
-        &mut *ctx.accounts.fee_token_collector,
     );
 
     pool.sell(
         token_accounts,
         &ctx.accounts.dex_configuration_account,
+        ctx.accounts.referrer_token_account.as_deref(),
         amount,
         min_output_amount,
         &ctx.accounts.user,
         &ctx.accounts.token_program,
     )?;
+    if let Some((price_before, max_price_impact_bps)) = price_guard {
+        pool.ensure_price_impact(price_before, max_price_impact_bps)?;
+    }
+    Ok(())
+}
+
+pub fn sell_exact_out(
+    ctx: Context<Sell>,
+    exchange_amount_out: u64,
+    max_tokens_in: u64,
+    deadline: Option<i64>,
+    max_price_impact_bps: Option<u16>,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    let now = Clock::get()?.unix_timestamp;
+    pool.ensure_tradable(&ctx.accounts.dex_configuration_account, PAUSE_SELLS, deadline, now)?;
+
+    let price_guard = match max_price_impact_bps {
+        Some(max_price_impact_bps) => Some((pool.spot_price()?, max_price_impact_bps)),
+        None => None,
+    };
+
+    let token_accounts = (
+        &mut *ctx.accounts.token_mint,
+        &mut *ctx.accounts.pool_token_account,
+        &mut *ctx.accounts.user_token_account,
+        &mut *ctx.accounts.exchange_token_mint,
+        &mut *ctx.accounts.pool_exchange_token_account,
+        &mut *ctx.accounts.user_exchange_token_account,
+    );
+
+    pool.sell_exact_out(
+        token_accounts,
+        &ctx.accounts.dex_configuration_account,
+        ctx.accounts.referrer_token_account.as_deref(),
+        exchange_amount_out,
+        max_tokens_in,
+        &ctx.accounts.user,
+        &ctx.accounts.token_program,
+    )?;
+    if let Some((price_before, max_price_impact_bps)) = price_guard {
+        pool.ensure_price_impact(price_before, max_price_impact_bps)?;
+    }
     Ok(())
 }
 
@@ -43,12 +97,6 @@ pub struct Sell<'info> {
     )]
     pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
 
-    #[account(
-        mut,
-        constraint = dex_configuration_account.get_fee_collector() == fee_token_collector.key()
-    )]
-    pub fee_token_collector: Box<Account<'info, TokenAccount>>,
-
     #[account(
         mut,
         seeds = [
@@ -95,6 +143,18 @@ pub struct Sell<'info> {
     )]
     pub user_exchange_token_account: Box<Account<'info, TokenAccount>>,
 
+    // Optional referrer paid `referral_fee_bps` of the fee, in the exchange token. Must not
+    // route the referral share back to the trader, the pool or the protocol fee vault
+    #[account(
+        mut,
+        token::mint = exchange_token_mint,
+        constraint = referrer_token_account.owner != user.key() @ CustomError::InvalidReferrer,
+        constraint = referrer_token_account.key() != pool_exchange_token_account.key() @ CustomError::InvalidReferrer,
+        constraint = referrer_token_account.owner != pool.key() @ CustomError::InvalidReferrer,
+        constraint = referrer_token_account.owner != FeeVault::address(&exchange_token_mint.key()) @ CustomError::InvalidReferrer,
+    )]
+    pub referrer_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
     #[account(mut)]
     pub user: Signer<'info>,
     pub rent: Sysvar<'info, Rent>,
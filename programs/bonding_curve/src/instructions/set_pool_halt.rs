@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::CustomError,
+    state::{CurveConfiguration, LiquidityPool},
+};
+
+// Stops buys, sells and add_liquidity on a single pool, e.g. to delist a token flagged
+// for abuse without locking down the whole platform
+pub fn set_pool_halt(ctx: Context<SetPoolHalt>, halted: bool, reason: u8) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    pool.halted = halted;
+    pool.halt_reason = if halted { reason } else { 0 };
+
+    emit!(PoolHaltUpdated {
+        pool: pool.key(),
+        token_mint: pool.token,
+        halted,
+        reason: pool.halt_reason,
+        authority: ctx.accounts.authority.key(),
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct PoolHaltUpdated {
+    pub pool: Pubkey,
+    pub token_mint: Pubkey,
+    pub halted: bool,
+    pub reason: u8,
+    pub authority: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct SetPoolHalt<'info> {
+    #[account(
+        seeds = [CurveConfiguration::SEED.as_bytes()],
+        bump,
+        constraint = dex_configuration_account.get_admin() == authority.key()
+            || dex_configuration_account.get_pause_guardian() == authority.key()
+            @ CustomError::InvalidAuthority,
+    )]
+    pub dex_configuration_account: Box<Account<'info, CurveConfiguration>>,
+
+    #[account(
+        mut,
+        seeds = [
+            LiquidityPool::POOL_SEED_PREFIX.as_bytes(),
+            pool.token.as_ref(),
+            pool.exchange_token.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    // Either the admin or the pause guardian
+    pub authority: Signer<'info>,
+}
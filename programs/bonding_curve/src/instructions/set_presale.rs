@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::CustomError, state::LiquidityPool};
+
+// Opens the pool to allowlisted wallets only until `presale_end`, see presale_buy
+pub fn set_presale(
+    ctx: Context<SetPresale>,
+    merkle_root: [u8; 32],
+    presale_end: i64,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    pool.set_presale(merkle_root, presale_end, Clock::get()?.unix_timestamp)?;
+
+    emit!(PresaleUpdated {
+        pool: pool.key(),
+        token_mint: pool.token,
+        merkle_root: pool.merkle_root,
+        presale_end: pool.presale_end,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct PresaleUpdated {
+    pub pool: Pubkey,
+    pub token_mint: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub presale_end: i64,
+}
+
+#[derive(Accounts)]
+pub struct SetPresale<'info> {
+    #[account(
+        mut,
+        seeds = [
+            LiquidityPool::POOL_SEED_PREFIX.as_bytes(),
+            pool.token.as_ref(),
+            pool.exchange_token.as_ref()
+        ],
+        bump = pool.bump,
+        has_one = creator @ CustomError::NotCreator,
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    pub creator: Signer<'info>,
+}
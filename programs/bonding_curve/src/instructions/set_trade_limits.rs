@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::CustomError,
+    state::{LiquidityPool, TradeLimits},
+};
+
+// Lets the creator cap early buys before trading starts, see TradeLimits
+pub fn set_trade_limits(ctx: Context<SetTradeLimits>, limits: TradeLimits) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    pool.set_trade_limits(limits)?;
+
+    emit!(TradeLimitsUpdated {
+        pool: pool.key(),
+        token_mint: pool.token,
+        limits,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct TradeLimitsUpdated {
+    pub pool: Pubkey,
+    pub token_mint: Pubkey,
+    pub limits: TradeLimits,
+}
+
+#[derive(Accounts)]
+pub struct SetTradeLimits<'info> {
+    #[account(
+        mut,
+        seeds = [
+            LiquidityPool::POOL_SEED_PREFIX.as_bytes(),
+            pool.token.as_ref(),
+            pool.exchange_token.as_ref()
+        ],
+        bump = pool.bump,
+        has_one = creator @ CustomError::NotCreator,
+    )]
+    pub pool: Box<Account<'info, LiquidityPool>>,
+
+    pub creator: Signer<'info>,
+}
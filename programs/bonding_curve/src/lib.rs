@@ -7,6 +7,7 @@ pub mod state;
 pub mod utils;
 
 use crate::instructions::*;
+use crate::state::{ConfigChange, ConfigUpdate, CurveKind, FeeUpdate, RoleUpdate, TradeLimits};
 
 declare_id!("7NgLyB7iY8cXtfUjUH6VkdDuxHZzMh6QMD8AtmdiEoVC");
 
@@ -14,17 +15,18 @@ declare_id!("7NgLyB7iY8cXtfUjUH6VkdDuxHZzMh6QMD8AtmdiEoVC");
 pub mod bonding_curve {
     use super::*;
 
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize(
         ctx: Context<InitializeCurveConfiguration>,
         fee_percentage: u64,
         creation_fees: u64,
-        proportion: f64,
+        proportion: u64,
         fee_collector: Pubkey,
         fee_sol_collector: Pubkey,
         exchange_token_mint: Pubkey,
         initial_token_for_pool: u64,
         is_sol_fee: bool,
-        is_lockdown: bool,
+        pause_flags: u8,
     ) -> Result<()> {
         instructions::initialize(
             ctx,
@@ -36,54 +38,209 @@ pub mod bonding_curve {
             exchange_token_mint,
             initial_token_for_pool,
             is_sol_fee,
-            is_lockdown,
+            pause_flags,
         )
     }
 
     pub fn update_configuration(
         ctx: Context<UpdateConfiguration>,
-        fee_percentage: u64,
-        creation_fees: u64,
-        proportion: f64,
-        fee_collector: Pubkey,
-        fee_sol_collector: Pubkey,
-        exchange_token_mint: Pubkey,
-        initial_token_for_pool: u64,
-        is_sol_fee: bool,
-        is_lockdown: bool,
+        update: ConfigUpdate,
     ) -> Result<()> {
-        instructions::update_configuration(
+        instructions::update_configuration(ctx, update)
+    }
+
+    pub fn update_fees(ctx: Context<UpdateFees>, update: FeeUpdate) -> Result<()> {
+        instructions::update_fees(ctx, update)
+    }
+
+    pub fn set_pause_flags(ctx: Context<SetPauseFlags>, pause_flags: u8) -> Result<()> {
+        instructions::set_pause_flags(ctx, pause_flags)
+    }
+
+    pub fn queue_config_change(ctx: Context<QueueConfigChange>, change: ConfigChange) -> Result<()> {
+        instructions::queue_config_change(ctx, change)
+    }
+
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        instructions::execute_config_change(ctx)
+    }
+
+    pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+        instructions::cancel_config_change(ctx)
+    }
+
+    pub fn set_roles(ctx: Context<SetRoles>, update: RoleUpdate) -> Result<()> {
+        instructions::set_roles(ctx, update)
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::propose_admin(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin(ctx)
+    }
+
+    pub fn cancel_admin_proposal(ctx: Context<CancelAdminProposal>) -> Result<()> {
+        instructions::cancel_admin_proposal(ctx)
+    }
+
+    pub fn create_pool(
+        ctx: Context<CreateLiquidityPool>,
+        curve_kind: CurveKind,
+        open_time: i64,
+    ) -> Result<()> {
+        instructions::create_pool(ctx, curve_kind, open_time)
+    }
+
+    pub fn add_liquidity(ctx: Context<AddLiquidity>, open_time: Option<i64>) -> Result<()> {
+        instructions::add_liquidity(ctx, open_time)
+    }
+
+    pub fn buy(
+        ctx: Context<Buy>,
+        amount: u64,
+        min_output_amount: u64,
+        deadline: Option<i64>,
+        max_price_impact_bps: Option<u16>,
+    ) -> Result<()> {
+        instructions::buy(
             ctx,
-            fee_percentage,
-            creation_fees,
-            proportion,
-            fee_collector,
-            fee_sol_collector,
-            exchange_token_mint,
-            initial_token_for_pool,
-            is_sol_fee,
-            is_lockdown,
+            amount,
+            min_output_amount,
+            deadline,
+            max_price_impact_bps,
         )
     }
 
-    pub fn create_pool(ctx: Context<CreateLiquidityPool>) -> Result<()> {
-        instructions::create_pool(ctx)
+    pub fn buy_exact_out(
+        ctx: Context<Buy>,
+        token_amount: u64,
+        max_input_amount: u64,
+        deadline: Option<i64>,
+        max_price_impact_bps: Option<u16>,
+    ) -> Result<()> {
+        instructions::buy_exact_out(
+            ctx,
+            token_amount,
+            max_input_amount,
+            deadline,
+            max_price_impact_bps,
+        )
+    }
+
+    pub fn presale_buy(
+        ctx: Context<Buy>,
+        amount: u64,
+        min_output_amount: u64,
+        allocation: u64,
+        proof: Vec<[u8; 32]>,
+        deadline: Option<i64>,
+        max_price_impact_bps: Option<u16>,
+    ) -> Result<()> {
+        instructions::presale_buy(
+            ctx,
+            amount,
+            min_output_amount,
+            allocation,
+            proof,
+            deadline,
+            max_price_impact_bps,
+        )
+    }
+
+    pub fn sell(
+        ctx: Context<Sell>,
+        amount: u64,
+        min_output_amount: u64,
+        deadline: Option<i64>,
+        max_price_impact_bps: Option<u16>,
+    ) -> Result<()> {
+        instructions::sell(
+            ctx,
+            amount,
+            min_output_amount,
+            deadline,
+            max_price_impact_bps,
+        )
+    }
+
+    pub fn sell_exact_out(
+        ctx: Context<Sell>,
+        exchange_amount_out: u64,
+        max_tokens_in: u64,
+        deadline: Option<i64>,
+        max_price_impact_bps: Option<u16>,
+    ) -> Result<()> {
+        instructions::sell_exact_out(
+            ctx,
+            exchange_amount_out,
+            max_tokens_in,
+            deadline,
+            max_price_impact_bps,
+        )
+    }
+
+    pub fn set_pool_halt(ctx: Context<SetPoolHalt>, halted: bool, reason: u8) -> Result<()> {
+        instructions::set_pool_halt(ctx, halted, reason)
+    }
+
+    pub fn set_trade_limits(ctx: Context<SetTradeLimits>, limits: TradeLimits) -> Result<()> {
+        instructions::set_trade_limits(ctx, limits)
+    }
+
+    pub fn set_presale(
+        ctx: Context<SetPresale>,
+        merkle_root: [u8; 32],
+        presale_end: i64,
+    ) -> Result<()> {
+        instructions::set_presale(ctx, merkle_root, presale_end)
+    }
+
+    pub fn close_user_position(ctx: Context<CloseUserPosition>) -> Result<()> {
+        instructions::close_user_position(ctx)
+    }
+
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+        instructions::claim_creator_fees(ctx)
+    }
+
+    pub fn sweep_fees(ctx: Context<SweepFees>) -> Result<()> {
+        instructions::sweep_fees(ctx)
+    }
+
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        instructions::withdraw_fees(ctx, amount)
+    }
+
+    pub fn distribute_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DistributeFees<'info>>,
+    ) -> Result<()> {
+        instructions::distribute_fees(ctx)
+    }
+
+    pub fn withdraw_sol_fees(ctx: Context<WithdrawSolFees>, amount: u64) -> Result<()> {
+        instructions::withdraw_sol_fees(ctx, amount)
+    }
+
+    pub fn quote_buy(ctx: Context<QuotePool>, amount: u64) -> Result<()> {
+        instructions::quote_buy(ctx, amount)
     }
 
-    pub fn add_liquidity(ctx: Context<AddLiquidity>) -> Result<()> {
-        instructions::add_liquidity(ctx)
+    pub fn quote_sell(ctx: Context<QuotePool>, amount: u64) -> Result<()> {
+        instructions::quote_sell(ctx, amount)
     }
 
-    pub fn remove_liquidity(ctx: Context<RemoveLiquidity>) -> Result<()> {
-        instructions::remove_liquidity(ctx)
+    pub fn migrate(ctx: Context<Migrate>, funding_lamports: u64) -> Result<()> {
+        instructions::migrate(ctx, funding_lamports)
     }
 
-    pub fn buy(ctx: Context<Buy>, amount: u64, min_output_amount: u64) -> Result<()> {
-        instructions::buy(ctx, amount, min_output_amount)
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        instructions::migrate_config(ctx)
     }
 
-    pub fn sell(ctx: Context<Sell>, amount: u64, min_output_amount: u64) -> Result<()> {
-        instructions::sell(ctx, amount, min_output_amount)
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        instructions::migrate_pool(ctx)
     }
 
     pub fn create_token(
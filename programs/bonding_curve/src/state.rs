@@ -1,4 +1,4 @@
-use crate::errors::CustomError;
+use crate::{consts::*, errors::CustomError, utils::*};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Mint, Token, TokenAccount};
 
@@ -6,33 +6,73 @@ use anchor_spl::token::{self, Mint, Token, TokenAccount};
 pub struct CurveConfiguration {
     fee_percentage: u64,
     creation_fees: u64,
-    proportion: f64,
+    proportion: u64,
     fee_collector: Pubkey,
     fee_sol_collector: Pubkey,
     exchange_token_mint: Pubkey,
     admin: Pubkey,
     initial_token_for_pool: u64,
     is_sol_fee: bool,
-    is_lockdown: bool,
+    pause_flags: u8, // PAUSE_* bits from consts, checked per instruction
+    pending_admin: Pubkey,
+    fee_manager: Pubkey,        // Can change the fee fields
+    pause_guardian: Pubkey,     // Can only change pause_flags
+    migration_operator: Pubkey, // Runs migrate and proxy_initialize
+    // Timelocked economic change, see queue_config_change
+    pending_change: Option<PendingConfigChange>,
+    version: u8,           // Layout version, see migrate_config
+    creator_fee_bps: u64,  // Share of every trade fee, in bps of the fee, owed to the pool creator
+    referral_fee_bps: u64, // Share of the fee, in bps of the fee, paid to a trade's referrer
+    // Fee distribution table, only the first fee_recipient_count entries are set
+    fee_recipients: [FeeRecipient; 5],
+    fee_recipient_count: u8,
+    launch_fee_bps: u64, // Buy fee right after add_liquidity, 0 disables the launch fee
+    launch_fee_decay_slots: u64, // Slots over which the launch fee falls to fee_percentage
+    reserved: [u8; 116], // Room for new fields, carve them out of here
 }
 
 impl CurveConfiguration {
     pub const SEED: &'static str = "CurveConfiguration";
 
-    // Discriminator (8) + f64 (8) + Pubkey (32)
-    pub const ACCOUNT_SIZE: usize = 8 + 8 + 8 + 32 + 32 + 32 + 32 + 8 + 8 + 1 + 1;
+    pub const VERSION: u8 = 2;
 
+    pub const ACCOUNT_SIZE: usize = 8 + // discriminator
+        8 +  // fee_percentage: u64
+        8 +  // creation_fees: u64
+        8 +  // proportion: u64
+        32 + // fee_collector: Pubkey
+        32 + // fee_sol_collector: Pubkey
+        32 + // exchange_token_mint: Pubkey
+        32 + // admin: Pubkey
+        8 +  // initial_token_for_pool: u64
+        1 +  // is_sol_fee: bool
+        1 +  // pause_flags: u8
+        32 + // pending_admin: Pubkey
+        32 + // fee_manager: Pubkey
+        32 + // pause_guardian: Pubkey
+        32 + // migration_operator: Pubkey
+        1 + PendingConfigChange::SIZE + // pending_change: Option<PendingConfigChange>
+        1 +  // version: u8
+        8 +  // creator_fee_bps: u64
+        8 +  // referral_fee_bps: u64
+        FeeRecipient::SIZE * MAX_FEE_RECIPIENTS + // fee_recipients: [FeeRecipient; 5]
+        1 +  // fee_recipient_count: u8
+        8 +  // launch_fee_bps: u64
+        8 +  // launch_fee_decay_slots: u64
+        116; // reserved: [u8; 116]
+
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         fee_percentage: u64,         // + 8
         creation_fees: u64,          // + 8
-        proportion: f64,             // + 8
+        proportion: u64,             // + 8
         fee_collector: Pubkey,       // + 32
         fee_sol_collector: Pubkey,   // + 32
         exchange_token_mint: Pubkey, // + 32
         admin: Pubkey,               // + 32
         initial_token_for_pool: u64, // + 8
         is_sol_fee: bool,            // + 1
-        is_lockdown: bool,           // + 1
+        pause_flags: u8,             // + 1
     ) -> Self {
         Self {
             fee_percentage,
@@ -44,31 +84,210 @@ impl CurveConfiguration {
             admin,
             initial_token_for_pool,
             is_sol_fee,
-            is_lockdown,
+            pause_flags,
+            pending_admin: Pubkey::default(),
+            fee_manager: admin,
+            pause_guardian: admin,
+            migration_operator: admin,
+            pending_change: None,
+            version: Self::VERSION,
+            creator_fee_bps: 0,
+            referral_fee_bps: 0,
+            fee_recipients: [FeeRecipient::default(); MAX_FEE_RECIPIENTS],
+            fee_recipient_count: 0,
+            launch_fee_bps: 0,
+            launch_fee_decay_slots: 0,
+            reserved: [0; 116],
+        }
+    }
+
+    // Brings an account written by an older layout up to VERSION. Bytes past the old
+    // layout read as zero after the realloc, so only fields that need a value are set here.
+    pub fn upgrade(&mut self) -> Result<u8> {
+        let from_version = self.version;
+        if from_version >= Self::VERSION {
+            return err!(CustomError::AccountUpToDate);
         }
+        if from_version == 0 {
+            // v0 stored proportion as an f64
+            let legacy_proportion = f64::from_bits(self.proportion);
+            self.proportion = (legacy_proportion * PROPORTION_PRECISION as f64) as u64;
+            self.fee_manager = self.admin;
+            self.pause_guardian = self.admin;
+            self.migration_operator = self.admin;
+        }
+        if from_version < 2 && self.pause_flags != 0 {
+            // v1 stored a single is_lockdown bool in this byte
+            self.pause_flags = PAUSE_ALL;
+        }
+        self.version = Self::VERSION;
+        Ok(from_version)
     }
 
-    pub fn update_configuration(
+    // Applies only the fields set in `update`
+    pub fn update_configuration(&mut self, update: ConfigUpdate) -> Result<()> {
+        if let Some(initial_token_for_pool) = update.initial_token_for_pool {
+            self.initial_token_for_pool = initial_token_for_pool;
+        }
+        Ok(())
+    }
+
+    // Fee fields belong to the fee manager, see update_fees
+    pub fn update_fees(&mut self, update: FeeUpdate) -> Result<()> {
+        if let Some(creation_fees) = update.creation_fees {
+            self.creation_fees = creation_fees;
+        }
+        if let Some(is_sol_fee) = update.is_sol_fee {
+            self.is_sol_fee = is_sol_fee;
+        }
+        let creator_fee_bps = update.creator_fee_bps.unwrap_or(self.creator_fee_bps);
+        let referral_fee_bps = update.referral_fee_bps.unwrap_or(self.referral_fee_bps);
+        // Both are shares of the same fee
+        if creator_fee_bps + referral_fee_bps > BPS_DENOMINATOR {
+            return err!(CustomError::InvalidFee);
+        }
+        self.creator_fee_bps = creator_fee_bps;
+        self.referral_fee_bps = referral_fee_bps;
+        Ok(())
+    }
+
+    // Economic changes wait CONFIG_CHANGE_DELAY seconds before execute_config_change applies them
+    pub fn queue_config_change(
         &mut self,
-        fee_percentage: u64,
-        creation_fees: u64,
-        proportion: f64,
-        fee_collector: Pubkey,
-        fee_sol_collector: Pubkey,
-        exchange_token_mint: Pubkey,
-        initial_token_for_pool: u64,
-        is_sol_fee: bool,
-        is_lockdown: bool,
-    ) -> Result<()> {
-        self.fee_percentage = fee_percentage;
-        self.creation_fees = creation_fees;
-        self.proportion = proportion;
-        self.fee_collector = fee_collector;
-        self.fee_sol_collector = fee_sol_collector;
-        self.exchange_token_mint = exchange_token_mint;
-        self.initial_token_for_pool = initial_token_for_pool;
-        self.is_sol_fee = is_sol_fee;
-        self.is_lockdown = is_lockdown;
+        change: ConfigChange,
+        proposer: Pubkey,
+        now: i64,
+    ) -> Result<PendingConfigChange> {
+        if self.pending_change.is_some() {
+            return err!(CustomError::ConfigChangePending);
+        }
+        change.validate()?;
+        let execute_after = now
+            .checked_add(CONFIG_CHANGE_DELAY)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        let pending = PendingConfigChange {
+            change,
+            proposer,
+            execute_after,
+        };
+        self.pending_change = Some(pending.clone());
+        Ok(pending)
+    }
+
+    pub fn execute_config_change(&mut self, now: i64) -> Result<()> {
+        let pending = self
+            .pending_change
+            .take()
+            .ok_or(CustomError::NoPendingConfigChange)?;
+        if now < pending.execute_after {
+            return err!(CustomError::ConfigChangeNotReady);
+        }
+        let change = pending.change;
+        if let Some(fee_percentage) = change.fee_percentage {
+            self.fee_percentage = fee_percentage;
+        }
+        if let Some(proportion) = change.proportion {
+            self.proportion = proportion;
+        }
+        if let Some(fee_collector) = change.fee_collector {
+            self.fee_collector = fee_collector;
+        }
+        if let Some(fee_sol_collector) = change.fee_sol_collector {
+            self.fee_sol_collector = fee_sol_collector;
+        }
+        if let Some(exchange_token_mint) = change.exchange_token_mint {
+            self.exchange_token_mint = exchange_token_mint;
+        }
+        if let Some(fee_recipients) = change.fee_recipients {
+            self.set_fee_recipients(&fee_recipients);
+        }
+        if let Some(launch_fee_bps) = change.launch_fee_bps {
+            self.launch_fee_bps = launch_fee_bps;
+        }
+        if let Some(launch_fee_decay_slots) = change.launch_fee_decay_slots {
+            self.launch_fee_decay_slots = launch_fee_decay_slots;
+        }
+        Ok(())
+    }
+
+    pub fn cancel_config_change(&mut self) -> Result<PendingConfigChange> {
+        self.pending_change
+            .take()
+            .ok_or_else(|| error!(CustomError::NoPendingConfigChange))
+    }
+
+    pub fn set_pause_flags(&mut self, pause_flags: u8) -> Result<()> {
+        if pause_flags & !PAUSE_ALL != 0 {
+            return err!(CustomError::InvalidInput);
+        }
+        self.pause_flags = pause_flags;
+        Ok(())
+    }
+
+    pub fn set_roles(&mut self, update: RoleUpdate) -> Result<()> {
+        if let Some(fee_manager) = update.fee_manager {
+            self.fee_manager = fee_manager;
+        }
+        if let Some(pause_guardian) = update.pause_guardian {
+            self.pause_guardian = pause_guardian;
+        }
+        if let Some(migration_operator) = update.migration_operator {
+            self.migration_operator = migration_operator;
+        }
+        Ok(())
+    }
+
+    // Replaces the whole distribution table, validated by FeeRecipient::validate_table when queued
+    fn set_fee_recipients(&mut self, recipients: &[FeeRecipient]) {
+        self.fee_recipients = [FeeRecipient::default(); MAX_FEE_RECIPIENTS];
+        self.fee_recipients[..recipients.len()].copy_from_slice(recipients);
+        self.fee_recipient_count = recipients.len() as u8;
+    }
+
+    pub fn params(&self) -> ConfigurationParams {
+        ConfigurationParams {
+            fee_percentage: self.fee_percentage,
+            creation_fees: self.creation_fees,
+            proportion: self.proportion,
+            initial_token_for_pool: self.initial_token_for_pool,
+            fee_collector: self.fee_collector,
+            fee_sol_collector: self.fee_sol_collector,
+            exchange_token_mint: self.exchange_token_mint,
+            is_sol_fee: self.is_sol_fee,
+            pause_flags: self.pause_flags,
+            creator_fee_bps: self.creator_fee_bps,
+            referral_fee_bps: self.referral_fee_bps,
+            launch_fee_bps: self.launch_fee_bps,
+            launch_fee_decay_slots: self.launch_fee_decay_slots,
+        }
+    }
+
+    // First half of an admin transfer; the new admin still has to accept
+    pub fn propose_admin(&mut self, new_admin: Pubkey) -> Result<()> {
+        if new_admin == Pubkey::default() || new_admin == self.admin {
+            return err!(CustomError::InvalidInput);
+        }
+        self.pending_admin = new_admin;
+        Ok(())
+    }
+
+    pub fn accept_admin(&mut self, new_admin: Pubkey) -> Result<()> {
+        if self.pending_admin == Pubkey::default() {
+            return err!(CustomError::NoPendingAdmin);
+        }
+        if self.pending_admin != new_admin {
+            return err!(CustomError::InvalidAuthority);
+        }
+        self.admin = new_admin;
+        self.pending_admin = Pubkey::default();
+        Ok(())
+    }
+
+    pub fn cancel_admin_proposal(&mut self) -> Result<()> {
+        if self.pending_admin == Pubkey::default() {
+            return err!(CustomError::NoPendingAdmin);
+        }
+        self.pending_admin = Pubkey::default();
         Ok(())
     }
 
@@ -76,11 +295,31 @@ impl CurveConfiguration {
         self.admin
     }
 
+    pub fn get_pending_admin(&self) -> Pubkey {
+        self.pending_admin
+    }
+
+    pub fn get_fee_manager(&self) -> Pubkey {
+        self.fee_manager
+    }
+
+    pub fn get_pause_guardian(&self) -> Pubkey {
+        self.pause_guardian
+    }
+
+    pub fn get_migration_operator(&self) -> Pubkey {
+        self.migration_operator
+    }
+
+    pub fn get_pending_change(&self) -> Option<&PendingConfigChange> {
+        self.pending_change.as_ref()
+    }
+
     pub fn get_initial_token_for_pool(&self) -> u64 {
         self.initial_token_for_pool
     }
 
-    pub fn get_proportion(&self) -> f64 {
+    pub fn get_proportion(&self) -> u64 {
         self.proportion
     }
 
@@ -88,6 +327,22 @@ impl CurveConfiguration {
         self.fee_percentage
     }
 
+    pub fn get_creator_fee_bps(&self) -> u64 {
+        self.creator_fee_bps
+    }
+
+    pub fn get_referral_fee_bps(&self) -> u64 {
+        self.referral_fee_bps
+    }
+
+    pub fn get_launch_fee_bps(&self) -> u64 {
+        self.launch_fee_bps
+    }
+
+    pub fn get_launch_fee_decay_slots(&self) -> u64 {
+        self.launch_fee_decay_slots
+    }
+
     pub fn get_creation_fees(&self) -> u64 {
         self.creation_fees
     }
@@ -108,8 +363,238 @@ impl CurveConfiguration {
         self.is_sol_fee
     }
 
-    pub fn get_is_lockdown(&self) -> bool {
-        self.is_lockdown
+    pub fn get_fee_recipients(&self) -> &[FeeRecipient] {
+        &self.fee_recipients[..self.fee_recipient_count as usize]
+    }
+
+    pub fn get_pause_flags(&self) -> u8 {
+        self.pause_flags
+    }
+
+    // True when any of the PAUSE_* bits in `flags` is set
+    pub fn is_paused(&self, flags: u8) -> bool {
+        self.pause_flags & flags != 0
+    }
+}
+
+// Arguments of update_configuration; `None` leaves the current value untouched
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct ConfigUpdate {
+    pub initial_token_for_pool: Option<u64>,
+}
+
+// Arguments of update_fees; `None` leaves the current value untouched
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct FeeUpdate {
+    pub creation_fees: Option<u64>,
+    pub is_sol_fee: Option<bool>,
+    pub creator_fee_bps: Option<u64>,
+    pub referral_fee_bps: Option<u64>,
+}
+
+// Economic fields that only change through the timelock; `None` leaves the value untouched
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConfigChange {
+    pub fee_percentage: Option<u64>,
+    pub proportion: Option<u64>,
+    pub fee_collector: Option<Pubkey>,
+    pub fee_sol_collector: Option<Pubkey>,
+    pub exchange_token_mint: Option<Pubkey>,
+    pub fee_recipients: Option<Vec<FeeRecipient>>, // Replaces the whole distribution table
+    pub launch_fee_bps: Option<u64>,
+    pub launch_fee_decay_slots: Option<u64>,
+}
+
+impl ConfigChange {
+    // Option<u64> (9) x 4 + Option<Pubkey> (33) x 3 + Option<Vec<FeeRecipient>> (1 + 4 + 34 x 5)
+    pub const SIZE: usize =
+        9 + 9 + 33 + 33 + 33 + 1 + 4 + FeeRecipient::SIZE * MAX_FEE_RECIPIENTS + 9 + 9;
+
+    pub fn validate(&self) -> Result<()> {
+        if *self == ConfigChange::default() {
+            return err!(CustomError::InvalidInput);
+        }
+        if let Some(fee_percentage) = self.fee_percentage {
+            if !(1..=BPS_DENOMINATOR).contains(&fee_percentage) {
+                return err!(CustomError::InvalidFee);
+            }
+        }
+        if self.proportion == Some(0) {
+            return err!(CustomError::InvalidInput);
+        }
+        if let Some(fee_recipients) = &self.fee_recipients {
+            FeeRecipient::validate_table(fee_recipients)?;
+        }
+        // A full launch fee would leave nothing to trade with
+        if let Some(launch_fee_bps) = self.launch_fee_bps {
+            if launch_fee_bps >= BPS_DENOMINATOR {
+                return err!(CustomError::InvalidFee);
+            }
+        }
+        Ok(())
+    }
+
+    // True when the change only touches fields the fee manager owns: the trade and launch
+    // fees and the fee collectors
+    pub fn is_fee_only(&self) -> bool {
+        let fee_fields = ConfigChange {
+            fee_percentage: self.fee_percentage,
+            fee_collector: self.fee_collector,
+            fee_sol_collector: self.fee_sol_collector,
+            launch_fee_bps: self.launch_fee_bps,
+            launch_fee_decay_slots: self.launch_fee_decay_slots,
+            ..ConfigChange::default()
+        };
+        *self == fee_fields
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PendingConfigChange {
+    pub change: ConfigChange,
+    pub proposer: Pubkey,
+    pub execute_after: i64,
+}
+
+impl PendingConfigChange {
+    pub const SIZE: usize = ConfigChange::SIZE + 32 + 8;
+}
+
+// Arguments of set_roles; `None` keeps the current holder
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct RoleUpdate {
+    pub fee_manager: Option<Pubkey>,
+    pub pause_guardian: Option<Pubkey>,
+    pub migration_operator: Option<Pubkey>,
+}
+
+// One row of the fee distribution table
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeRecipient {
+    pub owner: Pubkey,   // Wallet whose exchange token account receives the share
+    pub weight_bps: u16, // Share of each distribution, in bps
+}
+
+impl FeeRecipient {
+    pub const SIZE: usize = 32 + 2;
+
+    // Weights are bps of what distribute_fees pays out and have to add up to
+    // BPS_DENOMINATOR; an empty table turns distribution off.
+    pub fn validate_table(recipients: &[FeeRecipient]) -> Result<()> {
+        if recipients.len() > MAX_FEE_RECIPIENTS {
+            return err!(CustomError::InvalidFeeRecipients);
+        }
+        let mut total_bps: u64 = 0;
+        for (index, recipient) in recipients.iter().enumerate() {
+            if recipient.owner == Pubkey::default()
+                || recipient.weight_bps == 0
+                || recipients[..index]
+                    .iter()
+                    .any(|other| other.owner == recipient.owner)
+            {
+                return err!(CustomError::InvalidFeeRecipients);
+            }
+            total_bps += recipient.weight_bps as u64;
+        }
+        if !recipients.is_empty() && total_bps != BPS_DENOMINATOR {
+            return err!(CustomError::InvalidFeeRecipients);
+        }
+        Ok(())
+    }
+}
+
+// Snapshot of the configurable fields, reported before and after an update
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConfigurationParams {
+    pub fee_percentage: u64,
+    pub creation_fees: u64,
+    pub proportion: u64,
+    pub initial_token_for_pool: u64,
+    pub fee_collector: Pubkey,
+    pub fee_sol_collector: Pubkey,
+    pub exchange_token_mint: Pubkey,
+    pub is_sol_fee: bool,
+    pub pause_flags: u8,
+    pub creator_fee_bps: u64,
+    pub referral_fee_bps: u64,
+    pub launch_fee_bps: u64,
+    pub launch_fee_decay_slots: u64,
+}
+
+// Protocol fees for one exchange mint, held by the vault's associated token account
+#[account]
+pub struct FeeVault {
+    pub exchange_token_mint: Pubkey,
+    pub bump: u8,
+    pub total_accrued: u64,   // Everything swept or paid in, never decreases
+    pub total_withdrawn: u64, // Everything sent out by withdraw_fees
+}
+
+impl FeeVault {
+    pub const SEED: &'static str = "fee_vault";
+
+    pub const ACCOUNT_SIZE: usize = 8 + // discriminator
+        32 + // exchange_token_mint: Pubkey
+        1 +  // bump: u8
+        8 +  // total_accrued: u64
+        8; // total_withdrawn: u64
+
+    // PDA of the vault holding fees paid in `exchange_token_mint`
+    pub fn address(exchange_token_mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[Self::SEED.as_bytes(), exchange_token_mint.as_ref()],
+            &crate::ID,
+        )
+        .0
+    }
+
+    pub fn record_accrual(&mut self, amount: u64) -> Result<()> {
+        self.total_accrued = self
+            .total_accrued
+            .checked_add(amount)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        Ok(())
+    }
+
+    pub fn record_withdrawal(&mut self, amount: u64) -> Result<()> {
+        self.total_withdrawn = self
+            .total_withdrawn
+            .checked_add(amount)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        Ok(())
+    }
+}
+
+// SOL creation fees, held as lamports on the vault account itself
+#[account]
+pub struct SolFeeVault {
+    pub bump: u8,
+    pub total_accrued: u64,
+    pub total_withdrawn: u64,
+}
+
+impl SolFeeVault {
+    pub const SEED: &'static str = "sol_fee_vault";
+
+    pub const ACCOUNT_SIZE: usize = 8 + // discriminator
+        1 +  // bump: u8
+        8 +  // total_accrued: u64
+        8; // total_withdrawn: u64
+
+    pub fn record_accrual(&mut self, amount: u64) -> Result<()> {
+        self.total_accrued = self
+            .total_accrued
+            .checked_add(amount)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        Ok(())
+    }
+
+    pub fn record_withdrawal(&mut self, amount: u64) -> Result<()> {
+        self.total_withdrawn = self
+            .total_withdrawn
+            .checked_add(amount)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        Ok(())
     }
 }
 
@@ -134,6 +619,156 @@ pub struct LiquidityPool {
     pub reserve_token: u64,    // Reserve amount of regular token
     pub reserve_exchange: u64, // Reserve amount of exchange token (replacing reserve_exchange)
     pub bump: u8,
+    pub curve_kind: CurveKind, // Pricing function chosen at create_pool
+    pub status: PoolStatus,    // Lifecycle stage, see PoolStatus::can_transition_to
+    pub completed_slot: u64,
+    pub completed_at: i64,
+    pub version: u8,               // Layout version, see migrate_pool
+    pub halted: bool,              // Per-pool stop set by set_pool_halt
+    pub halt_reason: u8,           // Free-form code recorded with the halt, 0 when trading
+    pub creator_fee_balance: u64,  // Unclaimed creator fees, held in the exchange token vault
+    pub protocol_fee_balance: u64, // Protocol fees not yet moved to the FeeVault by sweep_fees
+    pub funded_slot: u64,          // Slot of add_liquidity
+    pub funded_at: i64,            // Timestamp of add_liquidity, start of the trade limit window
+    pub trade_limits: TradeLimits, // Early-trading limits set by the creator
+    pub merkle_root: [u8; 32],     // Presale allowlist root, see utils::merkle
+    pub presale_end: i64,          // Only allowlisted wallets can buy until this timestamp
+    pub open_time: i64,            // No buys or sells before this timestamp
+    pub opened_slot: u64,          // Slot trading opened at, start of the launch fee window
+    pub reserved: [u8; 142],       // Room for new fields, carve them out of here
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolStatus {
+    Created,   // Pool account exists, vaults are empty
+    Funded,    // add_liquidity has seeded the vaults
+    Trading,   // At least one buy has gone through
+    Completed, // The curve reached the graduation threshold
+    Migrated,  // Liquidity moved into Raydium by migrate
+    Closed,    // Emptied by the retired remove_liquidity, only reachable by v0 pools
+}
+
+impl PoolStatus {
+    pub fn can_transition_to(&self, next: PoolStatus) -> bool {
+        matches!(
+            (self, next),
+            (PoolStatus::Created, PoolStatus::Funded)
+                | (PoolStatus::Funded, PoolStatus::Trading)
+                | (PoolStatus::Trading, PoolStatus::Completed)
+                | (PoolStatus::Completed, PoolStatus::Migrated)
+        )
+    }
+}
+
+// Per-pool limits on buys for the first `duration` seconds after funding; 0 disables a limit
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TradeLimits {
+    pub max_buy_amount: u64,    // Exchange tokens per buy, fee included
+    pub max_wallet_tokens: u64, // Project tokens bought per wallet, see UserPosition
+    pub duration: i64,
+}
+
+impl TradeLimits {
+    pub const SIZE: usize = 8 + 8 + 8;
+}
+
+// Tokens a wallet has bought from one pool, checked against TradeLimits::max_wallet_tokens
+#[account]
+pub struct UserPosition {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub bump: u8,
+    pub tokens_bought: u64,
+}
+
+impl UserPosition {
+    pub const SEED_PREFIX: &'static str = "user_position";
+
+    pub const ACCOUNT_SIZE: usize = 8 + // discriminator
+        32 + // pool: Pubkey
+        32 + // user: Pubkey
+        1 +  // bump: u8
+        8; // tokens_bought: u64
+
+    // Fills in the keys of a position that init_if_needed may just have created
+    pub fn open(&mut self, pool: Pubkey, user: Pubkey, bump: u8) {
+        self.pool = pool;
+        self.user = user;
+        self.bump = bump;
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurveKind {
+    // tokens_sold^2 / proportion, with proportion copied from CurveConfiguration at create_pool
+    // so a later config change cannot reprice a live pool
+    Quadratic {
+        proportion: u64,
+    },
+    ConstantProduct {
+        virtual_token_reserve: u64,
+        virtual_exchange_reserve: u64,
+    },
+    Linear {
+        base_price: u64,
+        slope: u64,
+    },
+    Exponential {
+        base_price: u64,
+        growth_bps: u64,
+    },
+}
+
+impl CurveKind {
+    // Variant tag (1) + largest variant (8 + 8)
+    pub const SIZE: usize = 1 + 8 + 8;
+
+    pub fn validate(&self) -> Result<()> {
+        let valid = match *self {
+            CurveKind::Quadratic { proportion } => proportion > 0,
+            CurveKind::ConstantProduct {
+                virtual_token_reserve,
+                virtual_exchange_reserve,
+            } => virtual_token_reserve > 0 && virtual_exchange_reserve > 0,
+            CurveKind::Linear { base_price, slope } => {
+                (base_price > 0 || slope > 0) && slope <= MAX_CURVE_SLOPE
+            }
+            CurveKind::Exponential {
+                base_price,
+                growth_bps,
+            } => base_price > 0 && growth_bps > 0 && growth_bps <= MAX_GROWTH_BPS,
+        };
+        if !valid {
+            return err!(CustomError::InvalidCurveParameters);
+        }
+        Ok(())
+    }
+
+    pub fn curve(&self) -> Box<dyn BondingCurve> {
+        match *self {
+            CurveKind::Quadratic { proportion } => Box::new(QuadraticCurve {
+                proportion: proportion as u128,
+            }),
+            CurveKind::ConstantProduct {
+                virtual_token_reserve,
+                virtual_exchange_reserve,
+            } => Box::new(ConstantProductCurve {
+                virtual_token_reserve: virtual_token_reserve as u128,
+                virtual_exchange_reserve: virtual_exchange_reserve as u128,
+            }),
+            CurveKind::Linear { base_price, slope } => Box::new(LinearCurve {
+                base_price: base_price as u128,
+                slope: slope as u128,
+            }),
+            CurveKind::Exponential {
+                base_price,
+                growth_bps,
+            } => Box::new(ExponentialCurve {
+                base_price: base_price as u128,
+                growth_bps: growth_bps as u128,
+            }),
+        }
+    }
 }
 
 #[event]
@@ -142,6 +777,10 @@ pub struct TradeEvent {
     pub token_mint: Pubkey,
     pub amount_in: u64,
     pub amount_out: u64,
+    pub fee: u64,
+    pub creator_fee: u64,         // Part of `fee` credited to the pool creator
+    pub referrer: Option<Pubkey>, // Owner of the referrer token account, if any
+    pub referral_fee: u64,        // Part of `fee` paid to the referrer
     pub reserve_token_before: u64,
     pub reserve_token_after: u64,
     pub reserve_exchange_before: u64,
@@ -157,10 +796,13 @@ pub struct AddLiquidity {
 }
 
 #[event]
-pub struct RemoveLiquidity {
+pub struct CurveCompleted {
     pub pool: Pubkey,
+    pub token_mint: Pubkey,
     pub reserve_token: u64,
     pub reserve_exchange: u64,
+    pub slot: u64,
+    pub timestamp: i64,
 }
 
 #[event]
@@ -172,22 +814,56 @@ pub struct RaydiumPoolCreated {
     pub creator: Pubkey,
 }
 
+#[event]
+pub struct LiquidityBurned {
+    pub raydium_pool: Pubkey,
+    pub lp_mint: Pubkey,
+    pub amount: u64,
+}
+
 impl LiquidityPool {
     pub const POOL_SEED_PREFIX: &'static str = "liquidity_pool";
+    pub const MIGRATION_AUTHORITY_SEED: &'static str = "migration_authority";
 
     // Discriminator (8) + Pubkey (32) + Pubkey (32) + totalsupply (8)
     // + reserve one (8) + reserve two (8) + Bump (1)
     pub const ACCOUNT_SIZE: usize = 8 + // discriminator
         32 + // creator: Pubkey
         32 + // token: Pubkey
-        32 + // exchange_token: Pubkey  
+        32 + // exchange_token: Pubkey
         8 +  // total_supply: u64
         8 +  // reserve_token: u64
         8 +  // reserve_exchange: u64
-        1; // bump: u8
+        1 +  // bump: u8
+        CurveKind::SIZE + // curve_kind: CurveKind
+        1 +  // status: PoolStatus
+        8 +  // completed_slot: u64
+        8 +  // completed_at: i64
+        1 +  // version: u8
+        1 +  // halted: bool
+        1 +  // halt_reason: u8
+        8 +  // creator_fee_balance: u64
+        8 +  // protocol_fee_balance: u64
+        8 +  // funded_slot: u64
+        8 +  // funded_at: i64
+        TradeLimits::SIZE + // trade_limits: TradeLimits
+        32 + // merkle_root: [u8; 32]
+        8 +  // presale_end: i64
+        8 +  // open_time: i64
+        8 +  // opened_slot: u64
+        142; // reserved: [u8; 142]
+
+    pub const VERSION: u8 = 1;
 
     // Constructor to initialize a LiquidityPool with two tokens and a bump for the PDA
-    pub fn new(creator: Pubkey, token: Pubkey, exchange_token: Pubkey, bump: u8) -> Self {
+    pub fn new(
+        creator: Pubkey,
+        token: Pubkey,
+        exchange_token: Pubkey,
+        bump: u8,
+        curve_kind: CurveKind,
+        open_time: i64,
+    ) -> Self {
         Self {
             creator,
             token,
@@ -196,10 +872,560 @@ impl LiquidityPool {
             reserve_token: 0_u64,
             reserve_exchange: 0_u64,
             bump,
+            curve_kind,
+            status: PoolStatus::Created,
+            completed_slot: 0_u64,
+            completed_at: 0_i64,
+            version: Self::VERSION,
+            halted: false,
+            halt_reason: 0,
+            creator_fee_balance: 0,
+            protocol_fee_balance: 0,
+            funded_slot: 0,
+            funded_at: 0,
+            trade_limits: TradeLimits::default(),
+            merkle_root: [0; 32],
+            presale_end: 0,
+            open_time,
+            opened_slot: 0,
+            reserved: [0; 142],
+        }
+    }
+
+    // Brings an account written by an older layout up to VERSION, see CurveConfiguration::upgrade
+    pub fn upgrade(&mut self, curve_config: &CurveConfiguration) -> Result<u8> {
+        let from_version = self.version;
+        if from_version >= Self::VERSION {
+            return err!(CustomError::AccountUpToDate);
+        }
+        if from_version == 0 {
+            // v0 pools were always quadratic on the config's proportion and had no status,
+            // so infer it from the reserves
+            self.curve_kind = CurveKind::Quadratic {
+                proportion: curve_config.get_proportion(),
+            };
+            self.status = if self.total_supply == 0 {
+                // remove_liquidity zeroed supply and reserves. A v0 pool that was never funded
+                // looks the same and is closed too, rather than risk reopening an emptied pool.
+                PoolStatus::Closed
+            } else if self.tokens_sold()? >= self.graduation_threshold()? {
+                PoolStatus::Completed
+            } else if self.reserve_token < self.total_supply {
+                PoolStatus::Trading
+            } else {
+                PoolStatus::Funded
+            };
+        }
+        self.version = Self::VERSION;
+        Ok(from_version)
+    }
+
+    fn accrue_fees(&mut self, creator_fee: u64, protocol_fee: u64) -> Result<()> {
+        self.creator_fee_balance = self
+            .creator_fee_balance
+            .checked_add(creator_fee)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        self.protocol_fee_balance = self
+            .protocol_fee_balance
+            .checked_add(protocol_fee)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        Ok(())
+    }
+
+    // Exchange tokens in the vault that belong to fee balances rather than the curve
+    pub fn unclaimed_fees(&self) -> Result<u64> {
+        self.creator_fee_balance
+            .checked_add(self.protocol_fee_balance)
+            .ok_or_else(|| error!(CustomError::OverflowOrUnderflowOccurred))
+    }
+
+    // Moves the pool to `next`, rejecting anything outside the lifecycle
+    pub fn set_status(&mut self, next: PoolStatus) -> Result<()> {
+        if !self.status.can_transition_to(next) {
+            return err!(CustomError::InvalidPoolStatus);
+        }
+        self.status = next;
+        Ok(())
+    }
+
+    // Limits can only change before the first buy
+    pub fn set_trade_limits(&mut self, limits: TradeLimits) -> Result<()> {
+        match self.status {
+            PoolStatus::Created | PoolStatus::Funded => {}
+            _ => return err!(CustomError::InvalidPoolStatus),
+        }
+        if limits.duration < 0 {
+            return err!(CustomError::InvalidInput);
+        }
+        self.trade_limits = limits;
+        Ok(())
+    }
+
+    // Moves the scheduled open, only possible until the first buy
+    // Marks the pool funded by add_liquidity. A pool that is already open starts its launch
+    // fee window here, a scheduled one at its first buy, see start_trading.
+    fn record_funding(&mut self, clock: &Clock) -> Result<()> {
+        self.set_status(PoolStatus::Funded)?;
+        self.funded_slot = clock.slot;
+        self.funded_at = clock.unix_timestamp;
+        if self.open_time <= clock.unix_timestamp {
+            self.opened_slot = clock.slot;
+        }
+        Ok(())
+    }
+
+    fn start_trading(&mut self, slot: u64) -> Result<()> {
+        self.set_status(PoolStatus::Trading)?;
+        if self.opened_slot == 0 {
+            self.opened_slot = slot;
+        }
+        Ok(())
+    }
+
+    pub fn set_open_time(&mut self, open_time: i64) -> Result<()> {
+        match self.status {
+            PoolStatus::Created | PoolStatus::Funded => {}
+            _ => return err!(CustomError::InvalidPoolStatus),
+        }
+        if self.merkle_root != [0; 32] && self.presale_end <= open_time.max(self.funded_at) {
+            return err!(CustomError::InvalidPresaleEnd);
+        }
+        self.open_time = open_time;
+        Ok(())
+    }
+
+    // Start of trading: the scheduled open_time, or funding if that came later
+    pub fn opens_at(&self) -> i64 {
+        self.open_time.max(self.funded_at)
+    }
+
+    pub fn ensure_open(&self, now: i64) -> Result<()> {
+        if now < self.open_time {
+            return err!(CustomError::PoolNotOpen);
+        }
+        Ok(())
+    }
+
+    // Sets or, with a zero root, clears the presale. Like the trade limits it is fixed once
+    // the first buy goes through. A presale has to outlast both now and the pool's opening,
+    // otherwise no allowlisted buy could ever land in it.
+    pub fn set_presale(&mut self, merkle_root: [u8; 32], presale_end: i64, now: i64) -> Result<()> {
+        match self.status {
+            PoolStatus::Created | PoolStatus::Funded => {}
+            _ => return err!(CustomError::InvalidPoolStatus),
+        }
+        if merkle_root == [0; 32] {
+            self.merkle_root = [0; 32];
+            self.presale_end = 0;
+        } else {
+            if presale_end <= now || presale_end <= self.opens_at() {
+                return err!(CustomError::InvalidPresaleEnd);
+            }
+            self.merkle_root = merkle_root;
+            self.presale_end = presale_end;
+        }
+        Ok(())
+    }
+
+    pub fn is_presale_active(&self, now: i64) -> bool {
+        self.merkle_root != [0; 32] && now < self.presale_end
+    }
+
+    // Public buys wait for the presale to end, presale_buy only runs while it lasts
+    pub fn ensure_presale(&self, is_presale_buy: bool, now: i64) -> Result<()> {
+        match (is_presale_buy, self.is_presale_active(now)) {
+            (false, true) => err!(CustomError::PresaleActive),
+            (true, false) => err!(CustomError::PresaleNotActive),
+            _ => Ok(()),
+        }
+    }
+
+    // Checks that `wallet` is on the allowlist with `allocation` (0 for no cap)
+    pub fn verify_presale(
+        &self,
+        wallet: &Pubkey,
+        allocation: u64,
+        proof: &[[u8; 32]],
+    ) -> Result<()> {
+        if !verify_merkle_proof(proof, self.merkle_root, presale_leaf(wallet, allocation)) {
+            return err!(CustomError::InvalidMerkleProof);
+        }
+        Ok(())
+    }
+
+    pub fn trade_limits_apply(&self, now: i64) -> bool {
+        now < self.opens_at().saturating_add(self.trade_limits.duration)
+    }
+
+    // A UserPosition only matters while buys are capped, by the trade limits or a presale
+    // allocation; after that close_user_position can return its rent
+    pub fn tracks_positions(&self, now: i64) -> bool {
+        self.trade_limits_apply(now) || self.is_presale_active(now)
+    }
+
+    // Adds a buy to the wallet's position and enforces the trade limits while they apply.
+    // Buys only have to pass a position while tracks_positions holds.
+    pub fn record_buy(
+        &self,
+        position: Option<&mut UserPosition>,
+        quote: &SwapQuote,
+        now: i64,
+    ) -> Result<()> {
+        let position = match position {
+            Some(position) => position,
+            None if self.tracks_positions(now) => return err!(CustomError::UserPositionRequired),
+            None => return Ok(()),
+        };
+        position.tokens_bought = position
+            .tokens_bought
+            .checked_add(quote.amount_out)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+
+        let limits = self.trade_limits;
+        if !self.trade_limits_apply(now) {
+            return Ok(());
+        }
+        if limits.max_buy_amount > 0 && quote.amount_in > limits.max_buy_amount {
+            return err!(CustomError::BuyLimitExceeded);
+        }
+        if limits.max_wallet_tokens > 0 && position.tokens_bought > limits.max_wallet_tokens {
+            return err!(CustomError::WalletLimitExceeded);
+        }
+        Ok(())
+    }
+
+    // Checks every trade handler runs first: `pause_flag` isn't set, the pool isn't halted and
+    // the trader's deadline, if any, hasn't passed
+    pub fn ensure_tradable(
+        &self,
+        curve_config: &CurveConfiguration,
+        pause_flag: u8,
+        deadline: Option<i64>,
+        now: i64,
+    ) -> Result<()> {
+        if curve_config.is_paused(pause_flag) {
+            return err!(CustomError::Lockdown);
+        }
+        if self.halted {
+            return err!(CustomError::PoolHalted);
+        }
+        if let Some(deadline) = deadline {
+            if now > deadline {
+                return err!(CustomError::DeadlineExceeded);
+            }
+        }
+        Ok(())
+    }
+
+    // Buys are open once the pool is funded; trading stops for good once the curve has completed
+    pub fn ensure_can_buy(&self) -> Result<()> {
+        match self.status {
+            PoolStatus::Funded | PoolStatus::Trading => Ok(()),
+            PoolStatus::Created => err!(CustomError::InvalidPoolStatus),
+            _ => err!(CustomError::CurveCompleted),
+        }
+    }
+
+    // Sells need tokens out in the wild, so the pool must be trading
+    pub fn ensure_can_sell(&self) -> Result<()> {
+        match self.status {
+            PoolStatus::Trading => Ok(()),
+            PoolStatus::Created | PoolStatus::Funded => err!(CustomError::InvalidPoolStatus),
+            _ => err!(CustomError::CurveCompleted),
+        }
+    }
+
+    // Project tokens that have left the pool through the curve
+    pub fn tokens_sold(&self) -> Result<u64> {
+        self.total_supply
+            .checked_sub(self.reserve_token)
+            .ok_or_else(|| error!(CustomError::OverflowOrUnderflowOccurred))
+    }
+
+    // Rejects a curve that cannot be priced all the way to graduation for this supply, e.g.
+    // a virtual reserve below the threshold or parameters that overflow. Without this the
+    // pool could never complete and its liquidity would be stuck.
+    pub fn ensure_curve_completes(&self) -> Result<()> {
+        let curve = self.curve_kind.curve();
+        let threshold = self.graduation_threshold()? as u128;
+        curve
+            .reserve_at(threshold, Rounding::Up)
+            .and_then(|_| curve.price_at(0))
+            .and_then(|_| curve.price_at(threshold))
+            .map_err(|_| error!(CustomError::InvalidCurveParameters))?;
+        Ok(())
+    }
+
+    // Tokens sold at which the curve is exhausted
+    pub fn graduation_threshold(&self) -> Result<u64> {
+        to_u64(mul_div(
+            self.total_supply as u128,
+            GRADUATION_BPS as u128,
+            BPS_DENOMINATOR as u128,
+            Rounding::Down,
+        )?)
+    }
+
+    // Buy fee at `slot`: launch_fee_bps right after funding, falling linearly to
+    // fee_percentage over launch_fee_decay_slots
+    pub fn buy_fee_bps(&self, curve_config: &CurveConfiguration, slot: u64) -> Result<u64> {
+        let base_fee = curve_config.get_fees();
+        let launch_fee = curve_config.get_launch_fee_bps();
+        let decay_slots = curve_config.get_launch_fee_decay_slots();
+        // A scheduled pool has no opened_slot until its first buy, which pays the full launch fee
+        let opened_slot = match self.status {
+            PoolStatus::Funded if self.opened_slot == 0 => slot,
+            _ => self.opened_slot,
+        };
+        let elapsed = slot.saturating_sub(opened_slot);
+        if launch_fee <= base_fee || elapsed >= decay_slots {
+            return Ok(base_fee);
+        }
+        let decayed = to_u64(mul_div(
+            (launch_fee - base_fee) as u128,
+            elapsed as u128,
+            decay_slots as u128,
+            Rounding::Down,
+        )?)?;
+        Ok(launch_fee - decayed)
+    }
+
+    // Marginal price at the current point of the curve, see BondingCurve::price_at
+    pub fn spot_price(&self) -> Result<u64> {
+        to_u64(
+            self.curve_kind
+                .curve()
+                .price_at(self.tokens_sold()? as u128)?,
+        )
+    }
+
+    // Rejects a trade that moved the marginal price from `price_before` by more than
+    // `max_price_impact_bps`. Handlers only read `price_before` when the trader set a limit.
+    pub fn ensure_price_impact(&self, price_before: u64, max_price_impact_bps: u16) -> Result<()> {
+        let price_after = self.spot_price()?;
+        if price_impact_bps(price_before, price_after)? > max_price_impact_bps as u64 {
+            return err!(CustomError::PriceImpactTooHigh);
+        }
+        Ok(())
+    }
+
+    // Prices an exact-input buy of `amount` exchange tokens (fee included).
+    // Inputs above what is left on the curve are clamped to the remaining cost.
+    pub fn quote_buy(&self, curve_config: &CurveConfiguration, amount: u64) -> Result<SwapQuote> {
+        self.ensure_can_buy()?;
+        if amount == 0 {
+            return err!(CustomError::InvalidAmount);
+        }
+        let curve = self.curve_kind.curve();
+        let fee_percentage = self.buy_fee_bps(curve_config, Clock::get()?.slot)?;
+        let sold = self.tokens_sold()?;
+        let threshold = self.graduation_threshold()?;
+        if sold >= threshold {
+            return err!(CustomError::InsufficientSupply);
+        }
+
+        let reserve_before = curve.reserve_at(sold as u128, Rounding::Down)?;
+        let remaining = curve
+            .reserve_at(threshold as u128, Rounding::Up)?
+            .checked_sub(reserve_before)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+
+        let fee = fee_amount(amount, fee_percentage)?;
+        let net_in = amount.checked_sub(fee).ok_or(CustomError::InvalidFee)?;
+
+        if net_in as u128 >= remaining {
+            let net_in = to_u64(remaining)?;
+            let amount_in = amount_with_fee(net_in, fee_percentage)?;
+            return Ok(SwapQuote {
+                amount_in,
+                amount_out: threshold - sold,
+                fee: amount_in - net_in,
+            });
+        }
+
+        let reserve_after = reserve_before
+            .checked_add(net_in as u128)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        let sold_after = curve.sold_at(reserve_after, threshold as u128)?;
+        let amount_out = to_u64(sold_after.saturating_sub(sold as u128))?;
+        if amount_out == 0 {
+            return err!(CustomError::InvalidAmount);
         }
+
+        Ok(SwapQuote {
+            amount_in: amount,
+            amount_out,
+            fee,
+        })
+    }
+
+    // Prices an exact-output buy of `token_amount` project tokens; `amount_in` includes the fee
+    pub fn quote_buy_exact_out(
+        &self,
+        curve_config: &CurveConfiguration,
+        token_amount: u64,
+    ) -> Result<SwapQuote> {
+        self.ensure_can_buy()?;
+        if token_amount == 0 {
+            return err!(CustomError::InvalidAmount);
+        }
+        let curve = self.curve_kind.curve();
+        let sold = self.tokens_sold()?;
+        let sold_after = sold
+            .checked_add(token_amount)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        if sold_after > self.graduation_threshold()? {
+            return err!(CustomError::AmountExceedsCurve);
+        }
+
+        let net_in = curve
+            .reserve_at(sold_after as u128, Rounding::Up)?
+            .checked_sub(curve.reserve_at(sold as u128, Rounding::Down)?)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        let net_in = to_u64(net_in)?;
+        let fee_percentage = self.buy_fee_bps(curve_config, Clock::get()?.slot)?;
+        let amount_in = amount_with_fee(net_in, fee_percentage)?;
+
+        Ok(SwapQuote {
+            amount_in,
+            amount_out: token_amount,
+            fee: amount_in - net_in,
+        })
+    }
+
+    // Prices an exact-input sell of `amount` project tokens; `amount_out` is net of the fee
+    pub fn quote_sell(&self, curve_config: &CurveConfiguration, amount: u64) -> Result<SwapQuote> {
+        self.ensure_can_sell()?;
+        if amount == 0 {
+            return err!(CustomError::InvalidAmount);
+        }
+        let curve = self.curve_kind.curve();
+        let sold = self.tokens_sold()?;
+        if amount > sold {
+            return err!(CustomError::TokenAmountToSellTooBig);
+        }
+
+        let reserve_before = curve.reserve_at(sold as u128, Rounding::Down)?;
+        let reserve_after = curve.reserve_at((sold - amount) as u128, Rounding::Up)?;
+        let gross_out = to_u64(reserve_before.saturating_sub(reserve_after))?;
+        if self.reserve_exchange < gross_out {
+            return err!(CustomError::NotEnoughExchangeTokenInVault);
+        }
+
+        let fee = fee_amount(gross_out, curve_config.get_fees())?;
+        let amount_out = gross_out.checked_sub(fee).ok_or(CustomError::InvalidFee)?;
+
+        Ok(SwapQuote {
+            amount_in: amount,
+            amount_out,
+            fee,
+        })
+    }
+
+    // Prices an exact-output sell paying `exchange_amount_out` to the user after the fee
+    pub fn quote_sell_exact_out(
+        &self,
+        curve_config: &CurveConfiguration,
+        exchange_amount_out: u64,
+    ) -> Result<SwapQuote> {
+        self.ensure_can_sell()?;
+        if exchange_amount_out == 0 {
+            return err!(CustomError::InvalidAmount);
+        }
+        let curve = self.curve_kind.curve();
+        let sold = self.tokens_sold()?;
+
+        let gross_out = amount_with_fee(exchange_amount_out, curve_config.get_fees())?;
+        if self.reserve_exchange < gross_out {
+            return err!(CustomError::NotEnoughExchangeTokenInVault);
+        }
+
+        let reserve_after = curve
+            .reserve_at(sold as u128, Rounding::Down)?
+            .checked_sub(gross_out as u128)
+            .ok_or(CustomError::NotEnoughExchangeTokenInVault)?;
+        let sold_after = to_u64(curve.sold_at(reserve_after, sold as u128)?)?;
+
+        Ok(SwapQuote {
+            amount_in: sold - sold_after,
+            amount_out: exchange_amount_out,
+            fee: gross_out - exchange_amount_out,
+        })
+    }
+
+    // Expands a priced trade with the pool state it would leave behind
+    pub fn quote_after(&self, swap: SwapQuote, is_buy: bool) -> Result<Quote> {
+        let (reserve_token, reserve_exchange) = if is_buy {
+            (
+                self.reserve_token.checked_sub(swap.amount_out),
+                self.reserve_exchange.checked_add(swap.amount_in - swap.fee),
+            )
+        } else {
+            (
+                self.reserve_token.checked_add(swap.amount_in),
+                self.reserve_exchange
+                    .checked_sub(swap.amount_out + swap.fee),
+            )
+        };
+        let reserves_after = Reserves {
+            token: reserve_token.ok_or(CustomError::OverflowOrUnderflowOccurred)?,
+            exchange: reserve_exchange.ok_or(CustomError::OverflowOrUnderflowOccurred)?,
+        };
+
+        let sold_after = self
+            .total_supply
+            .checked_sub(reserves_after.token)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        let price_after = to_u64(self.curve_kind.curve().price_at(sold_after as u128)?)?;
+
+        Ok(Quote {
+            amount_in: swap.amount_in,
+            amount_out: swap.amount_out,
+            fee: swap.fee,
+            price_after,
+            reserves_after,
+        })
     }
 }
 
+// Result of pricing a trade against the curve. `amount_in` includes the fee on buys,
+// `amount_out` excludes it on sells.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapQuote {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Reserves {
+    pub token: u64,
+    pub exchange: u64,
+}
+
+// Returned by the quote instructions through return data.
+// `price_after` is the exchange base units paid for the next whole token.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quote {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee: u64,
+    pub price_after: u64,
+    pub reserves_after: Reserves,
+}
+
+// Token side accounts of a pool in this order: token_mint, pool_token_account,
+// user_token_account, exchange_token_mint, pool_exchange_token_account,
+// user_exchange_token_account
+pub type PoolTokenAccounts<'a, 'info> = (
+    &'a mut Account<'info, Mint>,
+    &'a mut Account<'info, TokenAccount>,
+    &'a mut Account<'info, TokenAccount>,
+    &'a mut Account<'info, Mint>,
+    &'a mut Account<'info, TokenAccount>,
+    &'a mut Account<'info, TokenAccount>,
+);
+
 pub trait LiquidityPoolAccount<'info> {
     // Updates the token reserves in the liquidity pool
     fn update_reserves(&mut self, reserve_token: u64, reserve_exchange: u64) -> Result<()>;
@@ -207,75 +1433,88 @@ pub trait LiquidityPoolAccount<'info> {
     // Allows adding liquidity by depositing an amount of two tokens and getting back pool shares
     fn add_liquidity(
         &mut self,
-        token_accounts: (
-            &mut Account<'info, Mint>,         // token_mint
-            &mut Account<'info, TokenAccount>, // pool_token_account
-            &mut Account<'info, TokenAccount>, // user_token_account
-            &mut Account<'info, Mint>,         // exchange_token_mint
-            &mut Account<'info, TokenAccount>, // pool_exchange_token_account
-            &mut Account<'info, TokenAccount>, // user_exchange_token_account
-        ),
+        token_accounts: PoolTokenAccounts<'_, 'info>,
         curve_config: &Account<'info, CurveConfiguration>,
         authority: &Signer<'info>,
         token_program: &Program<'info, Token>,
     ) -> Result<()>;
 
-    // Allows removing liquidity by burning pool shares and receiving back a proportionate amount of tokens
-    fn remove_liquidity(
+    #[allow(clippy::too_many_arguments)]
+    fn buy(
         &mut self,
-        token_accounts: (
-            &mut Account<'info, Mint>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, Mint>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, TokenAccount>,
-        ),
+        // bonding_configuration_account: &Account<'info, CurveConfiguration>,
+        token_accounts: PoolTokenAccounts<'_, 'info>,
         curve_config: &Account<'info, CurveConfiguration>,
+        referrer: Option<&Account<'info, TokenAccount>>,
+        position: Option<&mut Account<'info, UserPosition>>,
+        amount: u64,
+        min_output_amount: u64,
         authority: &Signer<'info>,
         token_program: &Program<'info, Token>,
     ) -> Result<()>;
 
-    fn buy(
+    #[allow(clippy::too_many_arguments)]
+    fn buy_exact_out(
         &mut self,
-        // bonding_configuration_account: &Account<'info, CurveConfiguration>,
-        token_accounts: (
-            &mut Account<'info, Mint>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, Mint>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, TokenAccount>,
-        ),
+        token_accounts: PoolTokenAccounts<'_, 'info>,
         curve_config: &Account<'info, CurveConfiguration>,
-        amount: u64,
-        min_output_amount: u64,
+        referrer: Option<&Account<'info, TokenAccount>>,
+        position: Option<&mut Account<'info, UserPosition>>,
+        token_amount: u64,
+        max_input_amount: u64,
+        authority: &Signer<'info>,
+        token_program: &Program<'info, Token>,
+    ) -> Result<()>;
+
+    // Moves the funds of an already priced buy and updates the reserves
+    #[allow(clippy::too_many_arguments)]
+    fn settle_buy(
+        &mut self,
+        token_accounts: PoolTokenAccounts<'_, 'info>,
+        curve_config: &Account<'info, CurveConfiguration>,
+        referrer: Option<&Account<'info, TokenAccount>>,
+        position: Option<&mut Account<'info, UserPosition>>,
+        quote: SwapQuote,
         authority: &Signer<'info>,
         token_program: &Program<'info, Token>,
     ) -> Result<()>;
 
+    #[allow(clippy::too_many_arguments)]
     fn sell(
         &mut self,
         // bonding_configuration_account: &Account<'info, CurveConfiguration>,
-        token_accounts: (
-            &mut Account<'info, Mint>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, Mint>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, TokenAccount>,
-            &mut Account<'info, TokenAccount>,
-        ),
+        token_accounts: PoolTokenAccounts<'_, 'info>,
         curve_config: &Account<'info, CurveConfiguration>,
+        referrer: Option<&Account<'info, TokenAccount>>,
         amount: u64,
         min_output_amount: u64,
         authority: &Signer<'info>,
         token_program: &Program<'info, Token>,
     ) -> Result<()>;
 
+    #[allow(clippy::too_many_arguments)]
+    fn sell_exact_out(
+        &mut self,
+        token_accounts: PoolTokenAccounts<'_, 'info>,
+        curve_config: &Account<'info, CurveConfiguration>,
+        referrer: Option<&Account<'info, TokenAccount>>,
+        exchange_amount_out: u64,
+        max_tokens_in: u64,
+        authority: &Signer<'info>,
+        token_program: &Program<'info, Token>,
+    ) -> Result<()>;
+
+    // Moves the funds of an already priced sell and updates the reserves
+    fn settle_sell(
+        &mut self,
+        token_accounts: PoolTokenAccounts<'_, 'info>,
+        curve_config: &Account<'info, CurveConfiguration>,
+        referrer: Option<&Account<'info, TokenAccount>>,
+        quote: SwapQuote,
+        authority: &Signer<'info>,
+        token_program: &Program<'info, Token>,
+    ) -> Result<()>;
+
     fn transfer_token_from_pool(
         &self,
         from: &Account<'info, TokenAccount>,
@@ -303,19 +1542,15 @@ impl<'info> LiquidityPoolAccount<'info> for Account<'info, LiquidityPool> {
 
     fn add_liquidity(
         &mut self,
-        token_accounts: (
-            &mut Account<'info, Mint>,         // token_mint
-            &mut Account<'info, TokenAccount>, // pool_token_account
-            &mut Account<'info, TokenAccount>, // user_token_account
-            &mut Account<'info, Mint>,         // exchange_token_mint
-            &mut Account<'info, TokenAccount>, // pool_exchange_token_account
-            &mut Account<'info, TokenAccount>, // user_exchange_token_account
-        ),
+        token_accounts: PoolTokenAccounts<'_, 'info>,
         curve_config: &Account<'info, CurveConfiguration>,
         authority: &Signer<'info>,
         token_program: &Program<'info, Token>,
     ) -> Result<()> {
+        self.record_funding(&Clock::get()?)?;
         let initial_token_for_pool = curve_config.get_initial_token_for_pool();
+        self.total_supply = 1_000_000_000 * u64::pow(10, token_accounts.0.decimals as u32);
+        self.ensure_curve_completes()?;
 
         // Transfer regular token to pool
         self.transfer_token_to_pool(
@@ -336,7 +1571,6 @@ impl<'info> LiquidityPoolAccount<'info> for Account<'info, LiquidityPool> {
         )?;
 
         // Update pool state
-        self.total_supply = 1_000_000_000 * u64::pow(10, token_accounts.0.decimals as u32);
         self.update_reserves(token_accounts.0.supply, initial_token_for_pool)?;
         emit!(AddLiquidity {
             pool: self.key(),
@@ -346,119 +1580,122 @@ impl<'info> LiquidityPoolAccount<'info> for Account<'info, LiquidityPool> {
         Ok(())
     }
 
-    fn remove_liquidity(
+    fn buy(
         &mut self,
-        token_accounts: (
-            &mut Account<'info, Mint>,         // 0
-            &mut Account<'info, TokenAccount>, // 1
-            &mut Account<'info, TokenAccount>, // 2
-            &mut Account<'info, Mint>,         // 3
-            &mut Account<'info, TokenAccount>, // 4
-            &mut Account<'info, TokenAccount>, // 5
-            &mut Account<'info, TokenAccount>, // 6
-            &mut Account<'info, TokenAccount>, // 7
-        ),
+        token_accounts: PoolTokenAccounts<'_, 'info>,
         curve_config: &Account<'info, CurveConfiguration>,
+        referrer: Option<&Account<'info, TokenAccount>>,
+        position: Option<&mut Account<'info, UserPosition>>,
+        amount: u64,
+        min_output_amount: u64,
         authority: &Signer<'info>,
         token_program: &Program<'info, Token>,
     ) -> Result<()> {
-        if authority.key() != curve_config.admin {
-            return err!(CustomError::InvalidAuthority);
+        let quote = self.quote_buy(curve_config, amount)?;
+
+        if quote.amount_out > self.reserve_token {
+            return err!(CustomError::NotEnoughTokenInVault);
         }
 
-        // Transfer all regular tokens from pool to user
-        self.transfer_token_from_pool(
-            token_accounts.1, // pool_token_account
-            token_accounts.6, // admin_token_account
-            token_accounts.1.amount,
-            token_program,
-        )?;
+        if quote.amount_out < min_output_amount {
+            return err!(CustomError::MinOutputAmountNotMet);
+        }
 
-        // Transfer all exchange tokens from pool to user
-        self.transfer_token_from_pool(
-            token_accounts.4, // pool_exchange_token_account
-            token_accounts.7, // admin_exchange_token_account
-            token_accounts.4.amount,
+        self.settle_buy(
+            token_accounts,
+            curve_config,
+            referrer,
+            position,
+            quote,
+            authority,
             token_program,
-        )?;
-
-        emit!(RemoveLiquidity {
-            pool: self.key(),
-            reserve_token: self.reserve_token,
-            reserve_exchange: self.reserve_exchange,
-        });
-
-        // Update pool state
-        self.update_reserves(0, 0)?;
-        self.total_supply = 0;
-
-        Ok(())
+        )
     }
 
-    fn buy(
+    fn buy_exact_out(
         &mut self,
-        token_accounts: (
-            &mut Account<'info, Mint>,         // Project token mint
-            &mut Account<'info, TokenAccount>, // Project token pool account
-            &mut Account<'info, TokenAccount>, // User's project token account
-            &mut Account<'info, Mint>,         // Exchange token mint
-            &mut Account<'info, TokenAccount>, // Exchange token pool account
-            &mut Account<'info, TokenAccount>, // User's exchange token account
-            &mut Account<'info, TokenAccount>, // Fee token account
-        ),
+        token_accounts: PoolTokenAccounts<'_, 'info>,
         curve_config: &Account<'info, CurveConfiguration>,
-        mut amount: u64,
-        min_output_amount: u64,
+        referrer: Option<&Account<'info, TokenAccount>>,
+        position: Option<&mut Account<'info, UserPosition>>,
+        token_amount: u64,
+        max_input_amount: u64,
         authority: &Signer<'info>,
         token_program: &Program<'info, Token>,
     ) -> Result<()> {
-        if amount == 0 {}
-        let proportion = curve_config.get_proportion();
-        let initial_token_for_pool = curve_config.get_initial_token_for_pool();
-        let fee_percentage = curve_config.get_fees();
-        let fee_amount = (amount / 10000) * fee_percentage;
-
-        let tokens_sold: f64 =
-            self.total_supply as f64 / 1_000_000_000.0 / 1_000_000.0 * 80.0 / 100.0;
-
-        let sol_needed: u64 =
-            (((tokens_sold * tokens_sold) / (proportion)) * 1_000_000_000.0).round() as u64;
-
-        if amount > sol_needed - self.reserve_exchange + fee_amount {
-            amount = sol_needed - self.reserve_exchange + fee_amount + initial_token_for_pool;
-        }
+        let quote = self.quote_buy_exact_out(curve_config, token_amount)?;
 
-        let bought_amount =
-            (self.total_supply as f64 - self.reserve_token as f64) / 1_000_000.0 / 1_000_000_000.0;
-
-        let root_val = (proportion as f64 * (amount - fee_amount) as f64 / 1_000_000_000.0
-            + bought_amount * bought_amount)
-            .sqrt();
-        let amount_out_f64 = (root_val - bought_amount as f64) * 1_000_000.0 * 1_000_000_000.0;
-        let amount_out = amount_out_f64.round() as u64;
-
-        if amount_out > self.reserve_token {
+        if quote.amount_out > self.reserve_token {
             return err!(CustomError::NotEnoughTokenInVault);
         }
 
-        if amount_out < min_output_amount {
-            return err!(CustomError::MinOutputAmountNotMet);
+        if quote.amount_in > max_input_amount {
+            return err!(CustomError::MaxInputAmountExceeded);
         }
 
-        // Transfer exchange tokens from user to fee token collector
-        self.transfer_token_to_pool(
-            token_accounts.5,
-            token_accounts.6,
-            fee_amount,
+        self.settle_buy(
+            token_accounts,
+            curve_config,
+            referrer,
+            position,
+            quote,
             authority,
             token_program,
-        )?;
+        )
+    }
+
+    fn settle_buy(
+        &mut self,
+        token_accounts: PoolTokenAccounts<'_, 'info>,
+        curve_config: &Account<'info, CurveConfiguration>,
+        referrer: Option<&Account<'info, TokenAccount>>,
+        position: Option<&mut Account<'info, UserPosition>>,
+        quote: SwapQuote,
+        authority: &Signer<'info>,
+        token_program: &Program<'info, Token>,
+    ) -> Result<()> {
+        let SwapQuote {
+            amount_in,
+            amount_out,
+            fee,
+        } = quote;
+        let net_in = amount_in - fee;
+        let creator_fee = fee_share(fee, curve_config.get_creator_fee_bps())?;
+        let referral_fee = match referrer {
+            Some(_) => fee_share(fee, curve_config.get_referral_fee_bps())?,
+            None => 0,
+        };
+
+        let clock = Clock::get()?;
+        let now = clock.unix_timestamp;
+        self.ensure_open(now)?;
+        self.record_buy(position.map(|position| &mut **position), &quote, now)?;
+
+        if self.status == PoolStatus::Funded {
+            self.start_trading(clock.slot)?;
+        }
+
+        let protocol_fee = fee - creator_fee - referral_fee;
+
+        // Transfer the referrer's fee share from user to referrer
+        if let Some(referrer) = referrer {
+            if referral_fee > 0 {
+                self.transfer_token_to_pool(
+                    token_accounts.5,
+                    referrer,
+                    referral_fee,
+                    authority,
+                    token_program,
+                )?;
+            }
+        }
 
-        // Transfer exchange tokens from user to pool
+        // Transfer exchange tokens from user to pool. The creator and protocol fee shares
+        // stay in the vault until claim_creator_fees / sweep_fees move them out.
         self.transfer_token_to_pool(
             token_accounts.5,
             token_accounts.4,
-            amount - fee_amount,
+            net_in + creator_fee + protocol_fee,
             authority,
             token_program,
         )?;
@@ -471,8 +1708,17 @@ impl<'info> LiquidityPoolAccount<'info> for Account<'info, LiquidityPool> {
             token_program,
         )?;
 
-        self.reserve_exchange += amount - fee_amount; // This becomes exchange token reserve
-        self.reserve_token -= amount_out;
+        let reserve_token_before = self.reserve_token;
+        let reserve_exchange_before = self.reserve_exchange;
+        self.reserve_exchange = self
+            .reserve_exchange
+            .checked_add(net_in)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        self.reserve_token = self
+            .reserve_token
+            .checked_sub(amount_out)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        self.accrue_fees(creator_fee, protocol_fee)?;
 
         msg!("reserve_token {}", self.reserve_token);
         msg!("reserve_exchange {}", self.reserve_exchange);
@@ -480,69 +1726,111 @@ impl<'info> LiquidityPoolAccount<'info> for Account<'info, LiquidityPool> {
         emit!(TradeEvent {
             pool: self.key(),
             token_mint: token_accounts.0.key(),
-            amount_in: amount,
+            amount_in,
             amount_out,
-            reserve_exchange_before: self.reserve_exchange,
+            fee,
+            creator_fee,
+            referrer: referrer.map(|referrer| referrer.owner),
+            referral_fee,
+            reserve_exchange_before,
             reserve_exchange_after: self.reserve_exchange,
-            reserve_token_before: self.reserve_token,
+            reserve_token_before,
             reserve_token_after: self.reserve_token,
             is_buy: true,
         });
+
+        if self.tokens_sold()? >= self.graduation_threshold()? {
+            let clock = Clock::get()?;
+            self.set_status(PoolStatus::Completed)?;
+            self.completed_slot = clock.slot;
+            self.completed_at = clock.unix_timestamp;
+
+            emit!(CurveCompleted {
+                pool: self.key(),
+                token_mint: token_accounts.0.key(),
+                reserve_token: self.reserve_token,
+                reserve_exchange: self.reserve_exchange,
+                slot: clock.slot,
+                timestamp: clock.unix_timestamp,
+            });
+        }
         Ok(())
     }
 
     fn sell(
         &mut self,
-        token_accounts: (
-            &mut Account<'info, Mint>,         // Project token mint
-            &mut Account<'info, TokenAccount>, // Project token pool account
-            &mut Account<'info, TokenAccount>, // User's project token account
-            &mut Account<'info, Mint>,         // Project token mint
-            &mut Account<'info, TokenAccount>, // Exchange token pool account
-            &mut Account<'info, TokenAccount>, // User's exchange token account
-            &mut Account<'info, TokenAccount>, // Fee token account
-        ),
+        token_accounts: PoolTokenAccounts<'_, 'info>,
         curve_config: &Account<'info, CurveConfiguration>,
+        referrer: Option<&Account<'info, TokenAccount>>,
         amount: u64,
         min_output_amount: u64,
         authority: &Signer<'info>,
         token_program: &Program<'info, Token>,
     ) -> Result<()> {
-        if amount == 0 {
-            return err!(CustomError::InvalidAmount);
-        }
-        let proportion = curve_config.get_proportion();
+        let quote = self.quote_sell(curve_config, amount)?;
 
-        if self.reserve_token < amount {
-            return err!(CustomError::TokenAmountToSellTooBig);
+        if quote.amount_out < min_output_amount {
+            return err!(CustomError::MinOutputAmountNotMet);
         }
 
-        let fee_percentage = curve_config.get_fees();
-
-        let bought_amount =
-            (self.total_supply as f64 - self.reserve_token as f64) / 1_000_000.0 / 1_000_000_000.0;
-        let result_amount = (self.total_supply as f64 - self.reserve_token as f64 - amount as f64)
-            / 1_000_000.0
-            / 1_000_000_000.0;
-        let amount_out_f64 = (bought_amount * bought_amount - result_amount * result_amount)
-            / proportion as f64
-            * 1_000_000_000.0;
-        let amount_out = amount_out_f64.round() as u64;
-        let fee_amount = amount_out * fee_percentage / 10000;
-        if self.reserve_exchange < amount_out {
-            // This checks exchange token reserve
-            return err!(CustomError::NotEnoughExchangeTokenInVault);
-        }
+        self.settle_sell(
+            token_accounts,
+            curve_config,
+            referrer,
+            quote,
+            authority,
+            token_program,
+        )
+    }
 
-        if amount_out < min_output_amount {
-            return err!(CustomError::MinOutputAmountNotMet);
+    fn sell_exact_out(
+        &mut self,
+        token_accounts: PoolTokenAccounts<'_, 'info>,
+        curve_config: &Account<'info, CurveConfiguration>,
+        referrer: Option<&Account<'info, TokenAccount>>,
+        exchange_amount_out: u64,
+        max_tokens_in: u64,
+        authority: &Signer<'info>,
+        token_program: &Program<'info, Token>,
+    ) -> Result<()> {
+        let quote = self.quote_sell_exact_out(curve_config, exchange_amount_out)?;
+
+        if quote.amount_in > max_tokens_in {
+            return err!(CustomError::MaxInputAmountExceeded);
         }
 
+        self.settle_sell(
+            token_accounts,
+            curve_config,
+            referrer,
+            quote,
+            authority,
+            token_program,
+        )
+    }
+
+    fn settle_sell(
+        &mut self,
+        token_accounts: PoolTokenAccounts<'_, 'info>,
+        curve_config: &Account<'info, CurveConfiguration>,
+        referrer: Option<&Account<'info, TokenAccount>>,
+        quote: SwapQuote,
+        authority: &Signer<'info>,
+        token_program: &Program<'info, Token>,
+    ) -> Result<()> {
+        let SwapQuote {
+            amount_in,
+            amount_out,
+            fee,
+        } = quote;
+
+        self.ensure_open(Clock::get()?.unix_timestamp)?;
+
         // Transfer project tokens from user to pool
         self.transfer_token_to_pool(
             token_accounts.2,
             token_accounts.1,
-            amount,
+            amount_in,
             authority,
             token_program,
         )?;
@@ -551,29 +1839,54 @@ impl<'info> LiquidityPoolAccount<'info> for Account<'info, LiquidityPool> {
         self.transfer_token_from_pool(
             token_accounts.4,
             token_accounts.5,
-            amount_out - fee_amount,
+            amount_out,
             token_program,
         )?;
 
-        // Transfer exchange tokens from pool to fee token collector
-        self.transfer_token_from_pool(
-            token_accounts.4,
-            token_accounts.6,
-            fee_amount,
-            token_program,
-        )?;
+        // The creator and protocol fee shares stay in the vault
+        let creator_fee = fee_share(fee, curve_config.get_creator_fee_bps())?;
+        let referral_fee = match referrer {
+            Some(_) => fee_share(fee, curve_config.get_referral_fee_bps())?,
+            None => 0,
+        };
+        let protocol_fee = fee - creator_fee - referral_fee;
+
+        // Transfer the referrer's fee share from pool to referrer
+        if let Some(referrer) = referrer {
+            if referral_fee > 0 {
+                self.transfer_token_from_pool(
+                    token_accounts.4,
+                    referrer,
+                    referral_fee,
+                    token_program,
+                )?;
+            }
+        }
 
-        self.reserve_token += amount;
-        self.reserve_exchange -= amount_out;
+        let reserve_token_before = self.reserve_token;
+        let reserve_exchange_before = self.reserve_exchange;
+        self.reserve_token = self
+            .reserve_token
+            .checked_add(amount_in)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        self.reserve_exchange = self
+            .reserve_exchange
+            .checked_sub(amount_out + fee)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        self.accrue_fees(creator_fee, protocol_fee)?;
 
         emit!(TradeEvent {
             pool: self.key(),
             token_mint: token_accounts.0.key(),
-            amount_in: amount,
+            amount_in,
             amount_out,
-            reserve_exchange_before: self.reserve_exchange,
+            fee,
+            creator_fee,
+            referrer: referrer.map(|referrer| referrer.owner),
+            referral_fee,
+            reserve_exchange_before,
             reserve_exchange_after: self.reserve_exchange,
-            reserve_token_before: self.reserve_token,
+            reserve_token_before,
             reserve_token_after: self.reserve_token,
             is_buy: false,
         });
@@ -630,3 +1943,128 @@ impl<'info> LiquidityPoolAccount<'info> for Account<'info, LiquidityPool> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FUNDED_SLOT: u64 = 1_000;
+    const FUNDED_AT: i64 = 1_700_000_000;
+    const DECAY_SLOTS: u64 = 100;
+
+    // 1% trade fee with a 50% launch fee falling over DECAY_SLOTS
+    fn launch_fee_config() -> CurveConfiguration {
+        let mut config = CurveConfiguration::new(
+            100,
+            0,
+            9_140_000,
+            Pubkey::default(),
+            Pubkey::default(),
+            Pubkey::default(),
+            Pubkey::default(),
+            0,
+            false,
+            0,
+        );
+        config.launch_fee_bps = 5_000;
+        config.launch_fee_decay_slots = DECAY_SLOTS;
+        config
+    }
+
+    fn funded_pool(open_time: i64) -> LiquidityPool {
+        let mut pool = LiquidityPool::new(
+            Pubkey::default(),
+            Pubkey::default(),
+            Pubkey::default(),
+            0,
+            CurveKind::Quadratic {
+                proportion: 9_140_000,
+            },
+            open_time,
+        );
+        let clock = Clock {
+            slot: FUNDED_SLOT,
+            unix_timestamp: FUNDED_AT,
+            ..Clock::default()
+        };
+        pool.record_funding(&clock).unwrap();
+        pool
+    }
+
+    #[test]
+    fn launch_fee_decays_from_funding_when_open_at_once() {
+        let config = launch_fee_config();
+        let mut pool = funded_pool(0);
+        assert_eq!(pool.buy_fee_bps(&config, FUNDED_SLOT).unwrap(), 5_000);
+
+        pool.start_trading(FUNDED_SLOT + 10).unwrap();
+        let halfway = FUNDED_SLOT + DECAY_SLOTS / 2;
+        assert_eq!(pool.buy_fee_bps(&config, halfway).unwrap(), 2_550);
+        let done = FUNDED_SLOT + DECAY_SLOTS;
+        assert_eq!(pool.buy_fee_bps(&config, done).unwrap(), 100);
+    }
+
+    #[test]
+    fn launch_fee_waits_for_a_scheduled_open() {
+        let config = launch_fee_config();
+        let mut pool = funded_pool(FUNDED_AT + 86_400);
+
+        // Long after funding the launch fee window has not started yet
+        let first_buy_slot = FUNDED_SLOT + 200_000;
+        assert_eq!(pool.buy_fee_bps(&config, first_buy_slot).unwrap(), 5_000);
+
+        pool.start_trading(first_buy_slot).unwrap();
+        assert_eq!(pool.buy_fee_bps(&config, first_buy_slot).unwrap(), 5_000);
+        let halfway = first_buy_slot + DECAY_SLOTS / 2;
+        assert_eq!(pool.buy_fee_bps(&config, halfway).unwrap(), 2_550);
+        let done = first_buy_slot + DECAY_SLOTS;
+        assert_eq!(pool.buy_fee_bps(&config, done).unwrap(), 100);
+    }
+
+    #[test]
+    fn buys_need_a_position_only_while_capped() {
+        let mut pool = funded_pool(0);
+        let quote = SwapQuote {
+            amount_in: 1_000,
+            amount_out: 5_000,
+            fee: 10,
+        };
+        assert!(pool.record_buy(None, &quote, FUNDED_AT).is_ok());
+
+        pool.trade_limits = TradeLimits {
+            max_buy_amount: 0,
+            max_wallet_tokens: 8_000,
+            duration: 60,
+        };
+        assert!(pool.record_buy(None, &quote, FUNDED_AT).is_err());
+        let mut position = UserPosition {
+            pool: Pubkey::default(),
+            user: Pubkey::default(),
+            bump: 0,
+            tokens_bought: 0,
+        };
+        assert!(pool
+            .record_buy(Some(&mut position), &quote, FUNDED_AT)
+            .is_ok());
+        assert!(pool
+            .record_buy(Some(&mut position), &quote, FUNDED_AT)
+            .is_err());
+        assert!(pool.record_buy(None, &quote, FUNDED_AT + 60).is_ok());
+    }
+
+    #[test]
+    fn presale_must_end_after_the_pool_opens() {
+        let root = [1; 32];
+        let open_time = FUNDED_AT + 3_600;
+        let mut pool = funded_pool(open_time);
+
+        assert!(pool.set_presale(root, FUNDED_AT, FUNDED_AT).is_err());
+        assert!(pool.set_presale(root, open_time, FUNDED_AT).is_err());
+        pool.set_presale(root, open_time + 1, FUNDED_AT).unwrap();
+        assert!(pool.set_open_time(open_time + 1).is_err());
+
+        // Clearing ignores the end
+        pool.set_presale([0; 32], 0, FUNDED_AT).unwrap();
+        assert_eq!(pool.presale_end, 0);
+    }
+}
@@ -1,9 +1,327 @@
-use std::ops::{Div, Mul};
+use anchor_lang::prelude::*;
 
-pub fn convert_to_float(value: u64, decimals: u8) -> f64 {
-    (value as f64).div(f64::powf(10.0, decimals as f64))
+use crate::{consts::*, errors::CustomError};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+pub fn div_round(numerator: u128, denominator: u128, rounding: Rounding) -> Result<u128> {
+    if denominator == 0 {
+        return err!(CustomError::OverflowOrUnderflowOccurred);
+    }
+    let quotient = numerator / denominator;
+    if rounding == Rounding::Up && quotient * denominator < numerator {
+        return quotient
+            .checked_add(1)
+            .ok_or_else(|| error!(CustomError::OverflowOrUnderflowOccurred));
+    }
+    Ok(quotient)
+}
+
+pub fn mul_div(a: u128, b: u128, denominator: u128, rounding: Rounding) -> Result<u128> {
+    let product = a
+        .checked_mul(b)
+        .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+    div_round(product, denominator, rounding)
+}
+
+// Floor of the square root, Newton's method
+pub fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value / 2 + 1;
+    let mut y = (x + value / x) / 2;
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+// `base^exponent` for a fixed-point base, by repeated squaring
+pub fn pow_fixed(base: u128, mut exponent: u128, rounding: Rounding) -> Result<u128> {
+    let mut result = FIXED_POINT_ONE;
+    let mut square = base;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_div(result, square, FIXED_POINT_ONE, rounding)?;
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            square = mul_div(square, square, FIXED_POINT_ONE, rounding)?;
+        }
+    }
+    Ok(result)
+}
+
+pub fn to_u64(value: u128) -> Result<u64> {
+    u64::try_from(value).map_err(|_| error!(CustomError::Overflow))
+}
+
+// Fee charged on `amount`, rounded up so the trader never underpays
+pub fn fee_amount(amount: u64, fee_bps: u64) -> Result<u64> {
+    to_u64(mul_div(
+        amount as u128,
+        fee_bps as u128,
+        BPS_DENOMINATOR as u128,
+        Rounding::Up,
+    )?)
+}
+
+// Part of `fee` owed to a share of `share_bps`, rounded down so the shares never exceed the fee
+pub fn fee_share(fee: u64, share_bps: u64) -> Result<u64> {
+    to_u64(mul_div(
+        fee as u128,
+        share_bps as u128,
+        BPS_DENOMINATOR as u128,
+        Rounding::Down,
+    )?)
 }
 
-pub fn convert_from_float(value: f64, decimals: u8) -> u64 {
-    value.mul(f64::powf(10.0, decimals as f64)) as u64
+// Relative move from `before` to `after` in bps, rounded up so a limit is never overshot
+pub fn price_impact_bps(before: u64, after: u64) -> Result<u64> {
+    let delta = before.abs_diff(after);
+    if delta == 0 {
+        return Ok(0);
+    }
+    if before == 0 {
+        return Ok(u64::MAX);
+    }
+    to_u64(mul_div(
+        delta as u128,
+        BPS_DENOMINATOR as u128,
+        before as u128,
+        Rounding::Up,
+    )?)
+}
+
+// Smallest gross amount that still leaves `net` after the fee is taken out of it
+pub fn amount_with_fee(net: u64, fee_bps: u64) -> Result<u64> {
+    let remaining_bps = BPS_DENOMINATOR
+        .checked_sub(fee_bps)
+        .ok_or(CustomError::InvalidFee)?;
+    to_u64(mul_div(
+        net as u128,
+        BPS_DENOMINATOR as u128,
+        remaining_bps as u128,
+        Rounding::Up,
+    )?)
+}
+
+// Exchange token held by the quadratic curve once `sold` tokens are out:
+// (sold / CURVE_TOKEN_UNIT)^2 / proportion whole exchange tokens.
+pub fn quadratic_reserve(sold: u128, proportion: u128, rounding: Rounding) -> Result<u128> {
+    let sold_squared = sold
+        .checked_mul(sold)
+        .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+    let scaled = div_round(sold_squared, CURVE_TOKEN_UNIT, rounding)?;
+    let denominator = CURVE_TOKEN_UNIT
+        .checked_mul(proportion)
+        .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+    mul_div(
+        scaled,
+        CURVE_EXCHANGE_UNIT * PROPORTION_PRECISION,
+        denominator,
+        rounding,
+    )
+}
+
+// Inverse of `quadratic_reserve`: tokens sold once the curve holds `reserve`, rounded down
+pub fn quadratic_sold(reserve: u128, proportion: u128) -> Result<u128> {
+    let scaled = mul_div(reserve, proportion, PROPORTION_PRECISION, Rounding::Down)?;
+    let sold_squared = scaled
+        .checked_mul(CURVE_TOKEN_UNIT * CURVE_TOKEN_UNIT / CURVE_EXCHANGE_UNIT)
+        .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+    Ok(isqrt(sold_squared))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Default proportion of 9.14 with PROPORTION_PRECISION
+    const DEFAULT_PROPORTION: u128 = 9_140_000;
+
+    // One whole token in base units, at 9 decimals
+    const ONE_TOKEN: u128 = 1_000_000_000;
+
+    // Reserve in exchange base units as the f64 formula before fixed-point math computed it
+    fn legacy_reserve(sold: u128, proportion: u128) -> u128 {
+        let whole_sold = sold as f64 / 1_000_000.0 / 1_000_000_000.0;
+        let proportion = proportion as f64 / PROPORTION_PRECISION as f64;
+        ((whole_sold * whole_sold) / proportion * 1_000_000_000.0).round() as u128
+    }
+
+    #[test]
+    fn div_round_rounds_in_the_requested_direction() {
+        assert_eq!(div_round(10, 3, Rounding::Down).unwrap(), 3);
+        assert_eq!(div_round(10, 3, Rounding::Up).unwrap(), 4);
+        assert_eq!(div_round(9, 3, Rounding::Up).unwrap(), 3);
+        assert_eq!(div_round(0, 3, Rounding::Up).unwrap(), 0);
+        assert!(div_round(1, 0, Rounding::Down).is_err());
+    }
+
+    #[test]
+    fn isqrt_is_the_floor_square_root() {
+        for (value, root) in [
+            (0, 0),
+            (1, 1),
+            (2, 1),
+            (3, 1),
+            (4, 2),
+            (15, 3),
+            (16, 4),
+            (17, 4),
+        ] {
+            assert_eq!(isqrt(value), root);
+        }
+        let max_root = u64::MAX as u128;
+        assert_eq!(isqrt(max_root * max_root), max_root);
+        assert_eq!(isqrt(max_root * max_root - 1), max_root - 1);
+        assert_eq!(isqrt(u128::MAX), max_root);
+    }
+
+    #[test]
+    fn pow_fixed_edge_cases() {
+        let two = 2 * FIXED_POINT_ONE;
+        assert_eq!(pow_fixed(two, 0, Rounding::Down).unwrap(), FIXED_POINT_ONE);
+        assert_eq!(
+            pow_fixed(FIXED_POINT_ONE, 1_000, Rounding::Up).unwrap(),
+            FIXED_POINT_ONE
+        );
+        assert_eq!(
+            pow_fixed(two, 10, Rounding::Down).unwrap(),
+            1_024 * FIXED_POINT_ONE
+        );
+
+        let growth = FIXED_POINT_ONE + FIXED_POINT_ONE / 10_000;
+        let down = pow_fixed(growth, 1_000, Rounding::Down).unwrap();
+        let up = pow_fixed(growth, 1_000, Rounding::Up).unwrap();
+        assert!(down <= up);
+
+        assert!(pow_fixed(two, 200, Rounding::Down).is_err());
+    }
+
+    #[test]
+    fn amount_with_fee_is_the_smallest_gross_amount() {
+        assert_eq!(amount_with_fee(0, 100).unwrap(), 0);
+        assert_eq!(amount_with_fee(1_000, 0).unwrap(), 1_000);
+        assert!(amount_with_fee(1_000, BPS_DENOMINATOR).is_err());
+        assert!(amount_with_fee(u64::MAX, 100).is_err());
+
+        for fee_bps in [1, 30, 100, 2_500, 9_999] {
+            for net in [1, 7, 999, 1_000_000, 123_456_789_012] {
+                let gross = amount_with_fee(net, fee_bps).unwrap();
+                assert!(gross - fee_amount(gross, fee_bps).unwrap() >= net);
+                let smaller = gross - 1;
+                assert!(smaller - fee_amount(smaller, fee_bps).unwrap() < net);
+            }
+        }
+    }
+
+    #[test]
+    fn fee_amount_rounds_up_and_shares_round_down() {
+        assert_eq!(fee_amount(0, 100).unwrap(), 0);
+        assert_eq!(fee_amount(1, 1).unwrap(), 1);
+        assert_eq!(fee_amount(10_000, 100).unwrap(), 100);
+        assert_eq!(fee_share(1, 5_000).unwrap(), 0);
+        assert_eq!(fee_share(100, 2_500).unwrap(), 25);
+    }
+
+    #[test]
+    fn price_impact_rounds_up() {
+        assert_eq!(price_impact_bps(1_000, 1_000).unwrap(), 0);
+        assert_eq!(price_impact_bps(1_000, 1_100).unwrap(), 1_000);
+        assert_eq!(price_impact_bps(1_000, 900).unwrap(), 1_000);
+        assert_eq!(price_impact_bps(3, 4).unwrap(), 3_334);
+        assert_eq!(price_impact_bps(0, 0).unwrap(), 0);
+        assert_eq!(price_impact_bps(0, 1).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn quadratic_rounding_favors_the_pool() {
+        for proportion in [
+            1,
+            1_000,
+            PROPORTION_PRECISION,
+            DEFAULT_PROPORTION,
+            1_000_000_000,
+        ] {
+            for sold in [
+                0,
+                1,
+                999_999_999,
+                1_000_000_000_000_000,
+                123_456_789_012_345_678,
+                800_000_000_000_000_000,
+            ] {
+                let down = quadratic_reserve(sold, proportion, Rounding::Down).unwrap();
+                let up = quadratic_reserve(sold, proportion, Rounding::Up).unwrap();
+                assert!(down <= up);
+                // Paying the rounded-down reserve never buys more than `sold`
+                assert!(quadratic_sold(down, proportion).unwrap() <= sold);
+            }
+            // No reserve is ever credited with more tokens than it pays for
+            for reserve in [
+                0,
+                1,
+                1_000,
+                109_409_190,
+                70_021_881_838_075,
+                100_000_000_000_000,
+            ] {
+                let sold = quadratic_sold(reserve, proportion).unwrap();
+                assert!(quadratic_reserve(sold, proportion, Rounding::Up).unwrap() <= reserve);
+            }
+        }
+    }
+
+    #[test]
+    fn quadratic_round_trip_stays_within_one_token() {
+        for proportion in [PROPORTION_PRECISION, DEFAULT_PROPORTION, 100_000_000] {
+            for sold in [
+                1_000_000_000_000_000,
+                123_456_789_012_345_678,
+                500_000_000_000_000_000,
+                800_000_000_000_000_000,
+            ] {
+                let reserve = quadratic_reserve(sold, proportion, Rounding::Up).unwrap();
+                let back = quadratic_sold(reserve, proportion).unwrap();
+                assert!(back.abs_diff(sold) <= ONE_TOKEN);
+            }
+        }
+    }
+
+    #[test]
+    fn quadratic_matches_the_legacy_f64_formula() {
+        for proportion in [PROPORTION_PRECISION, DEFAULT_PROPORTION, 50_000_000] {
+            for sold in [
+                1_000_000_000_000_000,
+                123_000_000_000_000_000,
+                456_789_000_000_000_000,
+                800_000_000_000_000_000,
+            ] {
+                let legacy = legacy_reserve(sold, proportion);
+                let down = quadratic_reserve(sold, proportion, Rounding::Down).unwrap();
+                let up = quadratic_reserve(sold, proportion, Rounding::Up).unwrap();
+                assert!(down.abs_diff(legacy) <= 1);
+                assert!(up.abs_diff(legacy) <= 1);
+            }
+        }
+    }
+
+    #[test]
+    fn quadratic_does_not_overflow_at_max_supply() {
+        // 1e18 base units of supply, graduating at 80%, with the smallest proportion;
+        // the u128 intermediates must hold even when the reserve itself exceeds u64
+        let total_supply: u128 = 1_000_000_000_000_000_000;
+        let threshold = total_supply * GRADUATION_BPS as u128 / BPS_DENOMINATOR as u128;
+        let reserve = quadratic_reserve(threshold, 1, Rounding::Up).unwrap();
+        let sold = quadratic_sold(reserve, 1).unwrap();
+        assert!(sold <= threshold);
+    }
 }
@@ -0,0 +1,234 @@
+use anchor_lang::prelude::*;
+
+use crate::{consts::*, errors::CustomError};
+
+use super::calc::*;
+
+// Pricing function shared by every trade path. A curve is described by the amount
+// of exchange token it holds once `sold` project tokens have left the pool.
+pub trait BondingCurve {
+    // Exchange token held by the curve once `sold` tokens are out
+    fn reserve_at(&self, sold: u128, rounding: Rounding) -> Result<u128>;
+
+    // Largest `sold <= max_sold` whose reserve (rounded up) does not exceed `reserve`.
+    // Defaults to a bisection over `reserve_at`, which every curve is monotonic in.
+    fn sold_at(&self, reserve: u128, max_sold: u128) -> Result<u128> {
+        let fits = |sold: u128| matches!(self.reserve_at(sold, Rounding::Up), Ok(needed) if needed <= reserve);
+        if fits(max_sold) {
+            return Ok(max_sold);
+        }
+        let (mut low, mut high) = (0_u128, max_sold);
+        while low < high {
+            let mid = high - (high - low) / 2;
+            if fits(mid) {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        Ok(low)
+    }
+
+    // Marginal price: exchange base units paid for the next whole token after `sold`
+    fn price_at(&self, sold: u128) -> Result<u128> {
+        let next = sold
+            .checked_add(PRICE_TOKEN_UNIT)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        Ok(self
+            .reserve_at(next, Rounding::Up)?
+            .saturating_sub(self.reserve_at(sold, Rounding::Down)?))
+    }
+}
+
+// reserve = (sold / CURVE_TOKEN_UNIT)^2 / proportion whole exchange tokens
+pub struct QuadraticCurve {
+    pub proportion: u128,
+}
+
+impl BondingCurve for QuadraticCurve {
+    fn reserve_at(&self, sold: u128, rounding: Rounding) -> Result<u128> {
+        quadratic_reserve(sold, self.proportion, rounding)
+    }
+
+    fn sold_at(&self, reserve: u128, max_sold: u128) -> Result<u128> {
+        Ok(quadratic_sold(reserve, self.proportion)?.min(max_sold))
+    }
+}
+
+// x * y = k over virtual reserves, with the real exchange reserve on top of the virtual one
+pub struct ConstantProductCurve {
+    pub virtual_token_reserve: u128,
+    pub virtual_exchange_reserve: u128,
+}
+
+impl ConstantProductCurve {
+    fn invariant(&self) -> Result<u128> {
+        self.virtual_token_reserve
+            .checked_mul(self.virtual_exchange_reserve)
+            .ok_or_else(|| error!(CustomError::OverflowOrUnderflowOccurred))
+    }
+}
+
+impl BondingCurve for ConstantProductCurve {
+    fn reserve_at(&self, sold: u128, rounding: Rounding) -> Result<u128> {
+        let token_reserve = self
+            .virtual_token_reserve
+            .checked_sub(sold)
+            .filter(|reserve| *reserve > 0)
+            .ok_or(CustomError::InsufficientSupply)?;
+        div_round(self.invariant()?, token_reserve, rounding)?
+            .checked_sub(self.virtual_exchange_reserve)
+            .ok_or_else(|| error!(CustomError::OverflowOrUnderflowOccurred))
+    }
+
+    fn sold_at(&self, reserve: u128, max_sold: u128) -> Result<u128> {
+        let exchange_reserve = self
+            .virtual_exchange_reserve
+            .checked_add(reserve)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        let token_reserve = div_round(self.invariant()?, exchange_reserve, Rounding::Up)?;
+        Ok(self
+            .virtual_token_reserve
+            .saturating_sub(token_reserve)
+            .min(max_sold))
+    }
+}
+
+// price = base_price + slope per CURVE_TOKEN_UNIT sold, in exchange base units per whole token
+pub struct LinearCurve {
+    pub base_price: u128,
+    pub slope: u128,
+}
+
+impl BondingCurve for LinearCurve {
+    fn reserve_at(&self, sold: u128, rounding: Rounding) -> Result<u128> {
+        let base = mul_div(self.base_price, sold, PRICE_TOKEN_UNIT, rounding)?;
+        let sold_squared = mul_div(sold, sold, CURVE_TOKEN_UNIT, rounding)?;
+        let sloped = mul_div(sold_squared, self.slope, 2 * PRICE_TOKEN_UNIT, rounding)?;
+        base.checked_add(sloped)
+            .ok_or_else(|| error!(CustomError::OverflowOrUnderflowOccurred))
+    }
+}
+
+// Price starts at base_price (exchange base units per whole token) and compounds by
+// growth_bps for every CURVE_TOKEN_UNIT sold
+pub struct ExponentialCurve {
+    pub base_price: u128,
+    pub growth_bps: u128,
+}
+
+impl BondingCurve for ExponentialCurve {
+    fn reserve_at(&self, sold: u128, rounding: Rounding) -> Result<u128> {
+        let steps = sold / CURVE_TOKEN_UNIT;
+        let remainder = sold % CURVE_TOKEN_UNIT;
+        let growth = FIXED_POINT_ONE
+            + mul_div(
+                FIXED_POINT_ONE,
+                self.growth_bps,
+                BPS_DENOMINATOR as u128,
+                Rounding::Down,
+            )?;
+        let multiplier = pow_fixed(growth, steps, rounding)?;
+
+        // Completed steps form a geometric series: step_cost * (growth^n - 1) / (growth - 1)
+        let step_cost = mul_div(
+            self.base_price,
+            CURVE_TOKEN_UNIT,
+            PRICE_TOKEN_UNIT,
+            rounding,
+        )?;
+        let completed = mul_div(
+            step_cost,
+            multiplier - FIXED_POINT_ONE,
+            growth - FIXED_POINT_ONE,
+            rounding,
+        )?;
+
+        let price = self
+            .base_price
+            .checked_mul(multiplier)
+            .ok_or(CustomError::OverflowOrUnderflowOccurred)?;
+        let partial = mul_div(
+            price,
+            remainder,
+            FIXED_POINT_ONE * PRICE_TOKEN_UNIT,
+            rounding,
+        )?;
+
+        completed
+            .checked_add(partial)
+            .ok_or_else(|| error!(CustomError::OverflowOrUnderflowOccurred))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THRESHOLD: u128 = 800_000_000_000_000_000;
+
+    fn curves() -> Vec<Box<dyn BondingCurve>> {
+        vec![
+            Box::new(QuadraticCurve {
+                proportion: 9_140_000,
+            }),
+            Box::new(ConstantProductCurve {
+                virtual_token_reserve: 1_073_000_000_000_000_000,
+                virtual_exchange_reserve: 30_000_000_000,
+            }),
+            Box::new(LinearCurve {
+                base_price: 1_000,
+                slope: 10,
+            }),
+            Box::new(ExponentialCurve {
+                base_price: 1_000,
+                growth_bps: 10,
+            }),
+        ]
+    }
+
+    #[test]
+    fn reserve_is_monotonic_and_rounds_up_for_the_pool() {
+        for curve in curves() {
+            let mut previous = 0;
+            for step in 0..=8 {
+                let sold = THRESHOLD / 8 * step;
+                let down = curve.reserve_at(sold, Rounding::Down).unwrap();
+                let up = curve.reserve_at(sold, Rounding::Up).unwrap();
+                assert!(down <= up);
+                assert!(down >= previous);
+                previous = down;
+            }
+        }
+    }
+
+    #[test]
+    fn sold_at_never_exceeds_what_the_reserve_pays_for() {
+        for curve in curves() {
+            let full = curve.reserve_at(THRESHOLD, Rounding::Up).unwrap();
+            for reserve in [0, 1, full / 3, full / 2, full - 1, full, full * 2] {
+                let sold = curve.sold_at(reserve, THRESHOLD).unwrap();
+                assert!(sold <= THRESHOLD);
+                assert!(curve.reserve_at(sold, Rounding::Up).unwrap() <= reserve);
+            }
+        }
+    }
+
+    #[test]
+    fn price_grows_along_the_curve() {
+        for curve in curves() {
+            let start = curve.price_at(0).unwrap();
+            let end = curve.price_at(THRESHOLD).unwrap();
+            assert!(start <= end);
+        }
+    }
+
+    #[test]
+    fn constant_product_fails_past_its_virtual_reserve() {
+        let curve = ConstantProductCurve {
+            virtual_token_reserve: THRESHOLD,
+            virtual_exchange_reserve: 30_000_000_000,
+        };
+        assert!(curve.reserve_at(THRESHOLD, Rounding::Up).is_err());
+    }
+}
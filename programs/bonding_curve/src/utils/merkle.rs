@@ -0,0 +1,18 @@
+use anchor_lang::{prelude::*, solana_program::keccak};
+
+// Presale allowlist leaf: keccak256(wallet || allocation as little-endian u64)
+pub fn presale_leaf(wallet: &Pubkey, allocation: u64) -> [u8; 32] {
+    keccak::hashv(&[wallet.as_ref(), &allocation.to_le_bytes()]).to_bytes()
+}
+
+// Walks `proof` up from `leaf`, hashing each pair in sorted order, and compares with `root`
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            keccak::hashv(&[&node, sibling]).to_bytes()
+        } else {
+            keccak::hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    computed == root
+}
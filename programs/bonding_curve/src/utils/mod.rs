@@ -1,2 +1,6 @@
 pub mod calc;
+pub mod curve;
+pub mod merkle;
 pub use calc::*;
+pub use curve::*;
+pub use merkle::*;
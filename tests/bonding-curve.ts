@@ -115,13 +115,13 @@ describe("bonding_curve", () => {
           .initialize(
             new BN(100), // fee percentage
             new BN(0.1 * 10 ** 9), // creation fees
-            9.14, // proportion
+            new BN(9_140_000), // proportion (6 decimals)
             feeTokenCollector.address, // fee collector
             user.publicKey, // fee sol collector
             mint2,
             new BN(1_000_000_000), // initial token for pool
             false, // is_sol_fee
-            false // is_lockdown
+            0 // pause_flags
           )
           .accounts({
             dexConfigurationAccount: curveConfig,
@@ -165,17 +165,9 @@ describe("bonding_curve", () => {
   //       ComputeBudgetProgram.setComputeUnitLimit({ units: 20_000 }),
   //       ComputeBudgetProgram.setComputeUnitPrice({ microLamports: 1200_000 }),
   //       await program.methods
-  //         .updateConfiguration(
-  //           new BN(100), // fee percentage
-  //           new BN(0.1 * 10 ** 9), // creation fees
-  //           9.14, // proportion
-  //           feeTokenCollector.address, // fee collector
-  //           user.publicKey, // fee sol collector
-  //           mint2,
-  //           new BN(1_000_000_100), // initial token for pool
-  //           false, // is_sol_fee
-  //           false // is_lockdown
-  //         )
+  //         .updateConfiguration({
+  //           initialTokenForPool: new BN(1_000_000_100),
+  //         })
   //         .accounts({
   //           dexConfigurationAccount: curveConfig,
   //           admin: user.publicKey,
@@ -273,12 +265,19 @@ describe("bonding_curve", () => {
   //       program.programId
   //     );
 
-  //     // 7. Get fee collector account
-  //     const feeTokenCollector = await getOrCreateAssociatedTokenAccount(
-  //       connection,
-  //       user,
+  //     // 7. Get fee vaults
+  //     const [solFeeVault] = PublicKey.findProgramAddressSync(
+  //       [Buffer.from("sol_fee_vault")],
+  //       program.programId
+  //     );
+  //     const [feeVault] = PublicKey.findProgramAddressSync(
+  //       [Buffer.from("fee_vault"), mint2.toBuffer()],
+  //       program.programId
+  //     );
+  //     const feeVaultTokenAccount = await getAssociatedTokenAddress(
   //       mint2,
-  //       user.publicKey
+  //       feeVault,
+  //       true
   //     );
 
   //     // Create token with instruction
@@ -297,8 +296,10 @@ describe("bonding_curve", () => {
   //         metadataAccount,
   //         dexConfigurationAccount: curveConfig,
   //         userFeeTokenAccount: userFeeTokenAccount,
-  //         feeSolCollector: user.publicKey,
-  //         feeCollector: feeTokenCollector.address,
+  //         exchangeTokenMint: mint2,
+  //         solFeeVault,
+  //         feeVault,
+  //         feeVaultTokenAccount,
   //         tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
   //         tokenProgram: TOKEN_PROGRAM_ID,
   //         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -394,7 +395,7 @@ describe("bonding_curve", () => {
 
   //     // Create transaction
   //     const tx = await program.methods
-  //       .createPool()
+  //       .createPool({ quadratic: { proportion: new BN(0) } }, new BN(0))
   //       .accounts({
   //         pool: poolPda,
   //         tokenMint: mint1,
@@ -490,7 +491,7 @@ describe("bonding_curve", () => {
   //       ComputeBudgetProgram.setComputeUnitLimit({ units: 200_000 }),
   //       ComputeBudgetProgram.setComputeUnitPrice({ microLamports: 200_000 }),
   //       await program.methods
-  //         .addLiquidity()
+  //         .addLiquidity(null)
   //         .accounts({
   //           pool: poolPda,
   //           tokenMint: mint1,
@@ -545,10 +546,6 @@ describe("bonding_curve", () => {
   //       program.programId
   //     );
 
-  //     const curveConfigData = await program.account.curveConfiguration.fetch(
-  //       curveConfig
-  //     );
-  //     const feeCollector = curveConfigData.feeCollector;
 
   //     // Find pool PDA with both token mints
   //     const [poolPda] = PublicKey.findProgramAddressSync(
@@ -584,16 +581,20 @@ describe("bonding_curve", () => {
   //       user3.publicKey
   //     );
 
+  //     const [userPosition] = PublicKey.findProgramAddressSync(
+  //       [Buffer.from("user_position"), poolPda.toBuffer(), user3.publicKey.toBuffer()],
+  //       program.programId
+  //     );
+
   //     const amount = new BN(600 * 1_000_000_000); // Amount to buy
 
   //     const tx = new Transaction().add(
   //       ComputeBudgetProgram.setComputeUnitLimit({ units: 200_000 }),
   //       ComputeBudgetProgram.setComputeUnitPrice({ microLamports: 200_000 }),
   //       await program.methods
-  //         .buy(amount, new BN(0))
+  //         .buy(amount, new BN(0), null, null)
   //         .accounts({
   //           dexConfigurationAccount: curveConfig,
-  //           feeTokenCollector: feeCollector,
   //           pool: poolPda,
   //           outputTokenMint: mint1,
   //           inputTokenMint: mint2,
@@ -601,6 +602,8 @@ describe("bonding_curve", () => {
   //           poolInputTokenAccount: poolInputTokenAccount,
   //           userOutputTokenAccount: userOutputTokenAccount,
   //           userInputTokenAccount: userInputTokenAccount,
+  //           referrerTokenAccount: null,
+  //           userPosition,
   //           user: user3.publicKey,
   //           rent: SYSVAR_RENT_PUBKEY,
   //           systemProgram: SystemProgram.programId,
@@ -633,10 +636,6 @@ describe("bonding_curve", () => {
   //       [Buffer.from(curveSeed)],
   //       program.programId
   //     );
-  //     const curveConfigData = await program.account.curveConfiguration.fetch(
-  //       curveConfig
-  //     );
-  //     const feeCollector = curveConfigData.feeCollector;
 
   //     // Find pool PDA
   //     const [poolPda, bump] = PublicKey.findProgramAddressSync(
@@ -678,10 +677,9 @@ describe("bonding_curve", () => {
   //       ComputeBudgetProgram.setComputeUnitLimit({ units: 200_000 }),
   //       ComputeBudgetProgram.setComputeUnitPrice({ microLamports: 200_000 }),
   //       await program.methods
-  //         .sell(amount, new BN(0))
+  //         .sell(amount, new BN(0), null, null)
   //         .accounts({
   //           dexConfigurationAccount: curveConfig,
-  //           feeTokenCollector: feeCollector,
   //           pool: poolPda,
   //           tokenMint: mint1,
   //           exchangeTokenMint: mint2,
@@ -689,6 +687,7 @@ describe("bonding_curve", () => {
   //           poolExchangeTokenAccount: poolExchangeTokenAccount,
   //           userTokenAccount: userTokenAccount.address,
   //           userExchangeTokenAccount: userExchangeTokenAccount.address,
+  //           referrerTokenAccount: null,
   //           user: user3.publicKey,
   //           rent: SYSVAR_RENT_PUBKEY,
   //           systemProgram: SystemProgram.programId,
@@ -714,100 +713,17 @@ describe("bonding_curve", () => {
   //   }
   // });
 
-  // it("Remove liquidity", async () => {
-  //   try {
-  //     const [poolPda] = PublicKey.findProgramAddressSync(
-  //       [Buffer.from(POOL_SEED_PREFIX), mint1.toBuffer(), mint2.toBuffer()],
-  //       program.programId
-  //     );
-
-  //     const poolToken = await getAssociatedTokenAddress(mint1, poolPda, true);
-
-  //     const poolExchangeToken = await getAssociatedTokenAddress(
-  //       mint2,
-  //       poolPda,
-  //       true
-  //     );
-
-  //     const userAta1 = await getOrCreateAssociatedTokenAccount(
-  //       connection,
-  //       user,
-  //       mint1,
-  //       user.publicKey
-  //     );
-
-  //     const userExchangeToken = await getAssociatedTokenAddress(
-  //       mint2,
-  //       user.publicKey
-  //     );
-
-  //     const adminToken = await getAssociatedTokenAddress(mint1, user.publicKey);
-
-  //     const adminExchangeToken = await getAssociatedTokenAddress(
-  //       mint2,
-  //       user.publicKey
-  //     );
-
-  //     const [curveConfig] = PublicKey.findProgramAddressSync(
-  //       [Buffer.from(curveSeed)],
-  //       program.programId
-  //     );
-
-  //     const tx = new Transaction().add(
-  //       ComputeBudgetProgram.setComputeUnitLimit({ units: 200_000 }),
-  //       ComputeBudgetProgram.setComputeUnitPrice({ microLamports: 200_000 }),
-  //       await program.methods
-  //         .removeLiquidity()
-  //         .accounts({
-  //           pool: poolPda,
-  //           tokenMint: mint1,
-  //           exchangeTokenMint: mint2,
-  //           poolTokenAccount: poolToken,
-  //           userTokenAccount: userAta1.address,
-  //           adminTokenAccount: adminToken,
-  //           adminExchangeTokenAccount: adminExchangeToken,
-  //           userExchangeTokenAccount: userExchangeToken,
-  //           poolExchangeTokenAccount: poolExchangeToken,
-  //           curveConfig: curveConfig,
-  //           user: user.publicKey,
-  //           tokenProgram: TOKEN_PROGRAM_ID,
-  //           associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
-  //           systemProgram: SystemProgram.programId,
-  //         })
-  //         .instruction()
-  //     );
-  //     tx.feePayer = user.publicKey;
-  //     tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
-  //     console.log(await connection.simulateTransaction(tx));
-  //     const sig = await sendAndConfirmTransaction(connection, tx, [user], {
-  //       skipPreflight: true,
-  //     });
-  //     console.log(
-  //       "Successfully remove liquidity : ",
-  //       `https://solscan.io/tx/${sig}?cluster=custom&customUrl=http%3A%2F%2Flocalhost%3A8899`
-  //     );
-  //     // check balance token mint1 và mint2 của user và pool
-  //     const balance1 = await connection.getTokenAccountBalance(
-  //       userAta1.address
-  //     );
-  //     const balance2 = await connection.getTokenAccountBalance(
-  //       userExchangeToken
-  //     );
-  //     console.log("Balance token mint1 : ", balance1.value.uiAmount);
-  //     console.log("Balance token mint2 : ", balance2.value.uiAmount);
-  //     transactions.push(`https://explorer.solana.com/tx/${sig}?cluster=devnet`);
-  //     console.log("Transactions : ", transactions);
-  //   } catch (error) {
-  //     console.log("Error in removing liquidity", error);
-  //   }
-  // });
-
   // it("initialize proxy", async () => {
   //   const token0 = mint1;
   //   const token1 = mint2;
 
   //   const createPoolFee = createPoolFeeReceive;
 
+  //   const [curveConfig] = PublicKey.findProgramAddressSync(
+  //     [Buffer.from(curveSeed)],
+  //     program.programId
+  //   );
+
   //   const [auth] = await getAuthAddress(cpSwapProgram);
   //   const [poolAddress] = await getPoolAddress(
   //     configAddress,
@@ -891,6 +807,7 @@ describe("bonding_curve", () => {
   //     .accounts({
   //       cpSwapProgram: cpSwapProgram,
   //       creator: user.publicKey,
+  //       curveConfig: curveConfig,
   //       ammConfig: configAddress,
   //       authority: auth,
   //       poolState: poolAddress,